@@ -0,0 +1,124 @@
+//! Color parsing and contrast.
+
+use std::str::FromStr;
+
+pub use svgdom::Color;
+
+/// WCAG AA minimum contrast ratio of a normal text.
+pub static MIN_TEXT_CONTRAST: f64 = 4.5;
+
+/// Parses a hex, `rgb()`, `hsl()` or a named CSS color.
+pub fn parse(text: &str) -> Option<Color> {
+    let text = text.trim();
+
+    if let Some(args) = text.strip_prefix("hsl(").and_then(|s| s.strip_suffix(')')) {
+        return parse_hsl(args);
+    }
+
+    Color::from_str(text).ok()
+}
+
+fn parse_hsl(args: &str) -> Option<Color> {
+    let args: Vec<&str> = args.split(',').map(|s| s.trim()).collect();
+    if args.len() != 3 {
+        return None;
+    }
+
+    let hue: f64 = args[0].strip_suffix("deg").unwrap_or(args[0]).parse().ok()?;
+    let percent = |s: &str| -> Option<f64> {
+        let n: f64 = s.strip_suffix('%')?.parse().ok()?;
+        Some(n.clamp(0.0, 100.0) / 100.0)
+    };
+    let saturation = percent(args[1])?;
+    let lightness = percent(args[2])?;
+
+    Some(hsl_to_rgb(hue, saturation, lightness))
+}
+
+// https://www.w3.org/TR/css-color-3/#hsl-color
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> Color {
+    let hue = hue.rem_euclid(360.0) / 360.0;
+
+    let m2 = if lightness <= 0.5 {
+        lightness * (saturation + 1.0)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let m1 = lightness * 2.0 - m2;
+
+    let channel = |h: f64| {
+        let h = h.rem_euclid(1.0);
+        let n = if h * 6.0 < 1.0 {
+            m1 + (m2 - m1) * h * 6.0
+        } else if h * 2.0 < 1.0 {
+            m2
+        } else if h * 3.0 < 2.0 {
+            m1 + (m2 - m1) * (2.0 / 3.0 - h) * 6.0
+        } else {
+            m1
+        };
+
+        (n * 255.0).round() as u8
+    };
+
+    Color::new(channel(hue + 1.0 / 3.0), channel(hue), channel(hue - 1.0 / 3.0))
+}
+
+/// Returns a relative luminance as defined by WCAG 2.0.
+pub fn luminance(c: Color) -> f64 {
+    let channel = |n: u8| {
+        let n = n as f64 / 255.0;
+        if n <= 0.03928 { n / 12.92 } else { ((n + 0.055) / 1.055).powf(2.4) }
+    };
+
+    0.2126 * channel(c.red) + 0.7152 * channel(c.green) + 0.0722 * channel(c.blue)
+}
+
+/// Returns a contrast ratio as defined by WCAG 2.0. From 1 to 21.
+pub fn contrast_ratio(c1: Color, c2: Color) -> f64 {
+    let l1 = luminance(c1);
+    let l2 = luminance(c2);
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+/// Returns a text color readable on the background.
+///
+/// The preferred color is used when it has enough contrast. Otherwise black or white.
+pub fn text_color_on(bg: Color, preferred: Color) -> Color {
+    if contrast_ratio(bg, preferred) >= MIN_TEXT_CONTRAST {
+        return preferred;
+    }
+
+    let black = Color::new(0, 0, 0);
+    let white = Color::new(255, 255, 255);
+    if contrast_ratio(bg, black) > contrast_ratio(bg, white) { black } else { white }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_1() {
+        assert_eq!(parse("#3260cd"), Some(Color::new(0x32, 0x60, 0xcd)));
+        assert_eq!(parse("#fff"), Some(Color::new(255, 255, 255)));
+        assert_eq!(parse("rgb(255, 0, 10)"), Some(Color::new(255, 0, 10)));
+        assert_eq!(parse("hsl(120, 100%, 25%)"), Some(Color::new(0, 128, 0)));
+        assert_eq!(parse("hsl(0deg, 0%, 100%)"), Some(Color::new(255, 255, 255)));
+        assert_eq!(parse("Gold"), Some(Color::new(255, 215, 0)));
+        assert_eq!(parse("#32g0cd"), None);
+        assert_eq!(parse("bleu"), None);
+        assert_eq!(parse("hsl(120, 100, 25)"), None);
+    }
+
+    #[test]
+    fn contrast_1() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::new(255, 255, 255);
+        assert_eq!(contrast_ratio(black, white), 21.0);
+        assert_eq!(contrast_ratio(white, white), 1.0);
+
+        assert_eq!(text_color_on(Color::new(0x32, 0x60, 0xcd), white), white);
+        assert_eq!(text_color_on(Color::new(255, 215, 0), white), black);
+    }
+}
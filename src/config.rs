@@ -1,43 +1,182 @@
+use std::error;
+use std::fmt;
+
 use serde_json;
 use serde_json::Value;
 
 type JSMap = serde_json::Map<String, Value>;
 
+use color::{self, Color};
 use font::FontData;
-
-static DEFAULT_BAR_COLOR: &'static str = "#3260cd";
+use number_format::{self, NumberFormat, Precision};
+use schema;
+use theme::Theme;
+use palette::{ColorBy, Palette};
+use time;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Item<'a> {
     pub name: &'a str,
+    /// Set for range bars, which are drawn from `start` to `value`.
+    pub start: Option<f64>,
+    /// An end of the bar.
     pub value: f64,
-    pub color: &'a str,
+    /// Lower and upper error margins relative to the value.
+    pub error: Option<(f64, f64)>,
+    /// A target marker position.
+    pub target: Option<f64>,
+    pub color: Color,
+    pub annotation: Annotation<'a>,
 }
 
-#[derive(PartialEq, Debug)]
+impl<'a> Item<'a> {
+    /// Returns a length of the bar in values.
+    pub fn length(&self) -> f64 {
+        self.value - self.start.unwrap_or(0.0)
+    }
+}
+
+/// A position of the annotation relative to the bar.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Placement {
+    /// At the end inside the bar or after the bar when it's too short.
+    Auto,
+    InsideEnd,
+    InsideStart,
+    Outside,
+    Center,
+}
+
+/// A value label of the bar.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Annotation<'a> {
+    pub show: bool,
+    pub placement: Placement,
+    /// A text with `{name}`, `{value}`, `{suffix}` and `{percent}` placeholders.
+    pub template: Option<&'a str>,
+}
+
+impl<'a> Default for Annotation<'a> {
+    fn default() -> Annotation<'a> {
+        Annotation {
+            show: true,
+            placement: Placement::Auto,
+            template: None,
+        }
+    }
+}
+
+/// Items order.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Sort {
+    /// Config order.
+    None,
+    ValueDesc,
+    ValueAsc,
+    Name,
+}
+
+/// A type of item values.
+///
+/// Durations are stored in seconds and dates as Unix timestamps.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum ValueType {
+    #[default]
+    Number,
+    Duration,
+    Date,
+}
+
+/// Horizontal axis scale.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum Scale {
+    #[default]
+    Linear,
+    /// Logarithmic scale with the specified base.
+    Log(f64),
+}
+
+/// A direction of bars.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    /// Columns with the value axis on the left.
+    Vertical,
+}
+
+#[derive(PartialEq, Default, Debug)]
 pub struct HorAxis<'a> {
     pub title: Option<&'a str>,
     pub suffix: Option<&'a str>,
+    pub min_value: Option<f64>,
     pub max_value: Option<f64>,
     pub ticks: Option<Vec<f64>>,
     pub width: Option<u32>,
     pub round_tick_values: bool,
+    pub tick_count: Option<u32>,
+    pub tick_step: Option<f64>,
+    pub scale: Scale,
+    /// Truncate an outlier bar with a break marker.
+    pub axis_break: bool,
 }
 
-#[cfg(test)]
-impl<'a> Default for HorAxis<'a> {
-    fn default() -> HorAxis<'a> {
-        HorAxis {
-            title: None,
-            suffix: None,
-            max_value: None,
-            ticks: None,
-            width: None,
-            round_tick_values: false,
+/// A vertical line across the plot area.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReferenceLine<'a> {
+    pub value: f64,
+    pub label: Option<&'a str>,
+    pub color: Color,
+}
+
+/// A shaded range of the plot area.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Band<'a> {
+    pub from: f64,
+    pub to: f64,
+    pub label: Option<&'a str>,
+    pub color: Color,
+}
+
+/// A named range of items.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Group<'a> {
+    pub name: &'a str,
+    /// An index of the first item.
+    pub start: usize,
+    pub len: usize,
+}
+
+/// A style of the region with bars.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlotArea<'a> {
+    /// `none` for a transparent background.
+    pub background: &'a str,
+    pub border: Option<&'a str>,
+    pub border_width: u32,
+    pub radius: u32,
+}
+
+impl<'a> Default for PlotArea<'a> {
+    fn default() -> PlotArea<'a> {
+        PlotArea {
+            background: "none",
+            border: None,
+            border_width: 1,
+            radius: 0,
         }
     }
 }
 
+/// Texts for screen readers.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct Accessibility<'a> {
+    /// Replaces the generated summary.
+    pub summary: Option<&'a str>,
+    /// Describe all items in a table.
+    pub data_table: bool,
+}
+
 #[derive(PartialEq, Debug)]
 pub struct Config<'a> {
     pub title: Option<&'a str>,
@@ -45,30 +184,74 @@ pub struct Config<'a> {
     pub debug: bool,
     pub items_font: FontData,
     pub hor_axis: Option<HorAxis<'a>>,
+    pub number_format: NumberFormat<'a>,
+    pub value_type: ValueType,
+    pub orientation: Orientation,
+    pub reference_lines: Vec<ReferenceLine<'a>>,
+    pub bands: Vec<Band<'a>>,
+    pub groups: Vec<Group<'a>>,
+    pub group_separator: bool,
+    pub theme: Theme<'a>,
+    pub plot_area: PlotArea<'a>,
+    /// A space around the chart.
+    pub padding: u32,
+    pub accessibility: Accessibility<'a>,
 }
 
 #[derive(PartialEq, Debug)]
 pub enum Error {
     ItemsIsNotSet,
-    ItemNameIsNotSet,
-    ItemValueIsNotSet,
-    ValueMustBePositive,
+    ItemNameIsNotSet(String), // item path
+    ItemValueIsNotSet(String), // item path
+    ValueMustBePositive(String), // value path
     CouldNotResolveFontPath,
-    InvalidValueType(&'static str, &'static str), // value path, expected type
+    InvalidValueType(String, &'static str), // value path, expected type
+    InvalidValue(String, &'static str), // value path, requirement
+    InvalidVariant(String, &'static [&'static str]), // value path, allowed values
+    ZeroValueOnLogScale(String), // value path
+    InvalidDuration(String),
+    InvalidDate(String),
+    ItemRangeIsIncomplete(String), // item path
+    InvalidColor(String, String), // value path, value
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::ItemsIsNotSet => write!(f, "'/items' is not set or empty"),
+            Error::ItemNameIsNotSet(ref path) => write!(f, "'{}/name' is not set", path),
+            Error::ItemValueIsNotSet(ref path) => write!(f, "'{}/value' is not set", path),
+            Error::ValueMustBePositive(ref path) => write!(f, "'{}' must be positive", path),
+            Error::CouldNotResolveFontPath => write!(f, "could not resolve font path"),
+            Error::InvalidValueType(ref path, kind) => {
+                write!(f, "'{}' must be a {}", path, kind)
+            }
+            Error::InvalidValue(ref path, req) => write!(f, "'{}' must be {}", path, req),
+            Error::InvalidVariant(ref path, list) => {
+                write!(f, "'{}' must be one of: {}", path, list.join(", "))
+            }
+            Error::ZeroValueOnLogScale(ref path) => {
+                write!(f, "'{}' must be greater than zero on a logarithmic scale", path)
+            }
+            Error::InvalidDuration(ref s) => write!(f, "'{}' is not a valid duration", s),
+            Error::InvalidDate(ref s) => write!(f, "'{}' is not a valid date", s),
+            Error::ItemRangeIsIncomplete(ref path) => {
+                write!(f, "'{0}/start' and '{0}/end' must be set together", path)
+            }
+            Error::InvalidColor(ref path, ref s) => {
+                write!(f, "'{}' must be a color, got '{}'", path, s)
+            }
+        }
+    }
 }
 
-// TODO: Error Display
+impl error::Error for Error {}
 
 macro_rules! value_option {
     ($obj:expr, $name:expr, $value_type:ident) => ({
         match $obj.get($name) {
-            Some(v) => {
-                match *v {
-                    Value::$value_type(ref t) => Some(t),
-                    _ => None,
-                }
-            }
-            None => None,
+            Some(&Value::$value_type(ref t)) => Some(t),
+            _ => None,
         }
     })
 }
@@ -98,35 +281,217 @@ impl<'a> Config<'a> {
             None => return Err(Error::ItemsIsNotSet),
         };
 
+        // types and enum variants of all known fields are checked here once,
+        // so the parsers below unwrap them and treat unknown variants as defaults
+        schema::validate(conf, schema::CONFIG_FIELDS)?;
+
         let items_obj = match conf.get("items") {
             Some(v) => {
                 match *v {
-                    Value::Array(ref t) => t,
-                    _ => return Err(Error::InvalidValueType("/items", "Array")),
+                    Value::Array(ref t) => t.as_slice(),
+                    _ => return Err(Error::InvalidValueType("/items".to_string(), "Array")),
                 }
             }
-            None => return Err(Error::ItemsIsNotSet),
+            None => &[],
+        };
+
+        let groups_obj = match conf.get("groups") {
+            Some(v) => v.as_array().unwrap().as_slice(),
+            None => &[],
         };
 
-        if items_obj.len() == 0 {
+        if items_obj.is_empty() && groups_obj.is_empty() {
             return Err(Error::ItemsIsNotSet);
         }
 
-        let items = parse_items(items_obj)?;
+        let value_type = match get_str!(conf, "value_type") {
+            Some("duration") => ValueType::Duration,
+            Some("date") => ValueType::Date,
+            _ => ValueType::Number,
+        };
 
-        let items_font = parse_font(conf, "items_font", system_font)?;
+        // numbers are in seconds by default
+        let unit = match get_str!(conf, "value_unit") {
+            Some(name) if value_type != ValueType::Number => time::unit_factor(name).unwrap(),
+            _ => 1.0,
+        };
 
         let hor_axis = match conf.get("hor_axis") {
-            Some(h) => Some(parse_hor_axis(h)?),
+            Some(h) => {
+                let mut ha = parse_hor_axis(h)?;
+                scale_hor_axis(&mut ha, unit);
+                Some(ha)
+            }
             None => None,
         };
 
+        let is_log = matches!(hor_axis, Some(HorAxis { scale: Scale::Log(_), .. }));
+
+        let mut theme = match conf.get("theme") {
+            Some(v) => parse_theme(v)?,
+            None => Theme::default(),
+        };
+
+        if let Some(color) = get_str!(conf, "background") {
+            check_paint(color, "/background")?;
+            theme.background = color;
+        }
+
+        let color_by = match get_str!(conf, "color_by") {
+            Some("name") => ColorBy::Name,
+            _ => ColorBy::Index,
+        };
+
+        let palette = match conf.get("palette") {
+            Some(Value::String(name)) => Palette::by_name(name, color_by).unwrap(),
+            Some(Value::Array(list)) => {
+                if list.is_empty() {
+                    return Err(Error::InvalidValue("/palette".to_string(), "a non-empty array"));
+                }
+
+                let mut colors = Vec::new();
+                for (i, v) in list.iter().enumerate() {
+                    colors.push(parse_color(v.as_str().unwrap(), &format!("/palette/{}", i))?);
+                }
+
+                Palette::new(colors, color_by)
+            }
+            _ => Palette::new(vec![parse_color(theme.bar_color, "/theme/bar_color")?], color_by),
+        };
+
+        let annotation = match conf.get("annotation") {
+            Some(v) => parse_annotation(v, Annotation::default()),
+            None => Annotation::default(),
+        };
+
+        let mut items = parse_items(items_obj, "/items", value_type, unit, &palette, 0,
+                                    annotation)?;
+        if is_log {
+            check_log_values(&items, "/items")?;
+        }
+
+        let sort = match get_str!(conf, "sort") {
+            Some("value_desc") => Sort::ValueDesc,
+            Some("value_asc") => Sort::ValueAsc,
+            Some("name") => Sort::Name,
+            _ => Sort::None,
+        };
+
+        let limit = match conf.get("limit").and_then(|v| v.as_u64()) {
+            Some(0) => return Err(Error::InvalidValue("/limit".to_string(), "at least 1")),
+            n => n.map(|n| n as usize),
+        };
+
+        let other = get_str!(conf, "other");
+        if other.is_some() && value_type == ValueType::Date {
+            return Err(Error::InvalidValue("/other".to_string(), "unset for dates"));
+        }
+
+        let other_color = parse_color(theme.other_bar_color, "/theme/other_bar_color")?;
+        let other = other.map(|name| Item {
+            name,
+            start: None,
+            value: 0.0,
+            error: None,
+            target: None,
+            color: other_color,
+            annotation,
+        });
+        arrange_items(&mut items, sort, limit, other);
+
+        // grouped items are stored after ungrouped ones
+        let mut groups = Vec::new();
+        // palette colors continue across groups, like in a single list
+        let mut palette_idx = items_obj.len();
+        for (i, group) in groups_obj.iter().enumerate() {
+            let name = match get_str!(group, "name") {
+                Some(name) => name,
+                None => return Err(Error::InvalidValue(format!("/groups/{}/name", i), "set")),
+            };
+
+            let path = format!("/groups/{}/items", i);
+            let list = match group.get("items").and_then(|v| v.as_array()) {
+                Some(list) if !list.is_empty() => list,
+                _ => return Err(Error::InvalidValue(path, "a non-empty array")),
+            };
+
+            let mut group_items = parse_items(list, &path, value_type, unit, &palette,
+                                              palette_idx, annotation)?;
+            palette_idx += list.len();
+            if is_log {
+                check_log_values(&group_items, &path)?;
+            }
+            arrange_items(&mut group_items, sort, limit, other);
+
+            groups.push(Group { name, start: items.len(), len: group_items.len() });
+            items.extend(group_items);
+        }
+
+        let items_font = parse_font(conf, "items_font", system_font)?;
+
+        let number_format = match conf.get("number_format") {
+            Some(v) => parse_number_format(v)?,
+            None => NumberFormat::default(),
+        };
+
+        let reference_lines = match conf.get("reference_lines").and_then(|v| v.as_array()) {
+            Some(list) => {
+                let color = parse_color(theme.reference_line_color, "/theme/reference_line_color")?;
+                parse_reference_lines(list, value_type, unit, color)?
+            }
+            None => Vec::new(),
+        };
+
+        let bands = match conf.get("bands").and_then(|v| v.as_array()) {
+            Some(list) => {
+                let color = parse_color(theme.band_color, "/theme/band_color")?;
+                parse_bands(list, value_type, unit, color)?
+            }
+            None => Vec::new(),
+        };
+
+        if is_log {
+            // everything drawn on the axis must have a logarithm
+            if let Some(i) = reference_lines.iter().position(|line| line.value <= 0.0) {
+                let path = format!("/reference_lines/{}/value", i);
+                return Err(Error::ZeroValueOnLogScale(path));
+            }
+
+            // `to` is not less than `from`
+            if let Some(i) = bands.iter().position(|band| band.from <= 0.0) {
+                return Err(Error::ZeroValueOnLogScale(format!("/bands/{}/from", i)));
+            }
+        }
+
         Ok(Config {
             title: get_str!(conf, "title"),
-            items: items,
+            items,
             debug: *value_option!(conf, "debug", Bool).unwrap_or(&false),
-            items_font: items_font,
-            hor_axis: hor_axis,
+            items_font,
+            hor_axis,
+            number_format,
+            value_type,
+            orientation: match get_str!(conf, "orientation") {
+                Some("vertical") => Orientation::Vertical,
+                _ => Orientation::Horizontal,
+            },
+            reference_lines,
+            bands,
+            groups,
+            group_separator: *value_option!(conf, "group_separator", Bool).unwrap_or(&false),
+            theme,
+            plot_area: match conf.get("plot_area") {
+                Some(v) => parse_plot_area(v)?,
+                None => PlotArea::default(),
+            },
+            padding: conf.get("padding").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+            accessibility: match conf.get("accessibility") {
+                Some(v) => Accessibility {
+                    summary: get_str!(v, "summary"),
+                    data_table: *value_option!(v, "data_table", Bool).unwrap_or(&false),
+                },
+                None => Accessibility::default(),
+            },
         })
     }
 }
@@ -140,53 +505,350 @@ impl<'a> Default for Config<'a> {
             debug: false,
             items_font: FontData::system_font().unwrap(),
             hor_axis: None,
+            number_format: NumberFormat::default(),
+            value_type: ValueType::Number,
+            orientation: Orientation::Horizontal,
+            reference_lines: Vec::new(),
+            bands: Vec::new(),
+            groups: Vec::new(),
+            group_separator: false,
+            theme: Theme::default(),
+            plot_area: PlotArea::default(),
+            padding: 0,
+            accessibility: Accessibility::default(),
         }
     }
 }
 
-fn parse_items(items: &Vec<Value>) -> Result<Vec<Item>, Error> {
+/// Parses items of the array at `path`.
+///
+/// Palette colors are assigned starting from `palette_idx`.
+fn parse_items<'a>(items: &'a [Value], path: &str, value_type: ValueType, unit: f64,
+                  palette: &Palette, palette_idx: usize, annotation: Annotation<'a>)
+    -> Result<Vec<Item<'a>>, Error>
+{
     let mut v = Vec::new();
 
-    for item in items {
+    for (idx, item) in items.iter().enumerate() {
+        let path = format!("{}/{}", path, idx);
         let obj = match item {
-            &Value::Object(ref t) => t,
-            _ => return Err(Error::InvalidValueType("/items/n", "Object")),
+            Value::Object(t) => t,
+            _ => return Err(Error::InvalidValueType(path, "Object")),
         };
 
         let name = match obj.get("name") {
             Some(v) => {
                 match v.as_str() {
                     Some(s) => s,
-                    None => return Err(Error::InvalidValueType("/items/n/name", "String")),
+                    None => return Err(Error::InvalidValueType(format!("{}/name", path), "String")),
                 }
             }
-            None => return Err(Error::ItemNameIsNotSet),
+            None => return Err(Error::ItemNameIsNotSet(path)),
         };
 
-        let value = match obj.get("value") {
-            Some(v) => {
-                match *v {
-                    Value::Number(ref t) => t.as_f64().unwrap(),
-                    _ => return Err(Error::InvalidValueType("/items/n/value", "Number")),
+        let (start, value) = match (obj.get("value"), obj.get("start"), obj.get("end")) {
+            (Some(v), None, None) => {
+                (None, parse_value(v, &format!("{}/value", path), value_type, unit)?)
+            }
+            (None, Some(start), Some(end)) => {
+                let start = parse_value(start, &format!("{}/start", path), value_type, unit)?;
+                let end = parse_value(end, &format!("{}/end", path), value_type, unit)?;
+                if end < start {
+                    return Err(Error::InvalidValue(format!("{}/end", path), "not less than start"));
+                }
+
+                (Some(start), end)
+            }
+            (None, None, None) => return Err(Error::ItemValueIsNotSet(path)),
+            _ => return Err(Error::ItemRangeIsIncomplete(path)),
+        };
+
+        // errors of dates are durations
+        let error_type = match value_type {
+            ValueType::Number => ValueType::Number,
+            _ => ValueType::Duration,
+        };
+
+        let error = match (obj.get("error"), obj.get("error_low"), obj.get("error_high")) {
+            (None, None, None) => None,
+            (error, low, high) => {
+                let error = match error {
+                    Some(v) => parse_value(v, &format!("{}/error", path), error_type, unit)?,
+                    None => 0.0,
+                };
+                let low = match low {
+                    Some(v) => parse_value(v, &format!("{}/error_low", path), error_type, unit)?,
+                    None => error,
+                };
+                let high = match high {
+                    Some(v) => parse_value(v, &format!("{}/error_high", path), error_type, unit)?,
+                    None => error,
+                };
+
+                if error < 0.0 || low < 0.0 || high < 0.0 {
+                    return Err(Error::InvalidValue(format!("{}/error", path), "positive"));
                 }
+
+                Some((low, high))
             }
-            None => return Err(Error::ItemValueIsNotSet),
         };
 
-        if value.is_sign_negative() {
-            return Err(Error::ValueMustBePositive);
+        let target = match obj.get("target") {
+            Some(v) => Some(parse_value(v, &format!("{}/target", path), value_type, unit)?),
+            None => None,
+        };
+
+        // dates before the epoch are negative
+        if value_type != ValueType::Date {
+            if start.is_some_and(|n| n.is_sign_negative()) {
+                return Err(Error::ValueMustBePositive(format!("{}/start", path)));
+            }
+
+            if value.is_sign_negative() {
+                let name = if start.is_some() { "end" } else { "value" };
+                return Err(Error::ValueMustBePositive(format!("{}/{}", path, name)));
+            }
         }
 
         v.push(Item {
-            name: name,
-            value: value,
-            color: get_str!(obj, "color").unwrap_or(DEFAULT_BAR_COLOR),
+            name,
+            start,
+            value,
+            error,
+            target,
+            color: match get_str!(obj, "color") {
+                Some(s) => parse_color(s, &format!("{}/color", path))?,
+                None => palette.color(palette_idx + idx, name),
+            },
+            annotation: match obj.get("annotation") {
+                Some(v) => parse_annotation(v, annotation),
+                None => annotation,
+            },
         });
     }
 
     Ok(v)
 }
 
+/// Checks that everything drawn for items of the array at `path` has a logarithm.
+fn check_log_values(items: &[Item], path: &str) -> Result<(), Error> {
+    for (i, item) in items.iter().enumerate() {
+        let name = match (item.start, item.target) {
+            (Some(n), _) if n <= 0.0 => "start",
+            (None, _) if item.value <= 0.0 => "value",
+            (_, Some(n)) if n <= 0.0 => "target",
+            _ => continue,
+        };
+
+        return Err(Error::ZeroValueOnLogScale(format!("{}/{}/{}", path, i, name)));
+    }
+
+    Ok(())
+}
+
+/// Sorts items and keeps only the first `limit` ones.
+///
+/// When `other` is set, the remaining items are collapsed into it
+/// with the summed length.
+fn arrange_items<'a>(items: &mut Vec<Item<'a>>, sort: Sort, limit: Option<usize>,
+                     other: Option<Item<'a>>) {
+    // the sort is stable, so equal items are kept in the config order
+    match sort {
+        Sort::None => {}
+        // range bars are compared by their length and not by their end
+        Sort::ValueDesc => items.sort_by(|a, b| b.length().partial_cmp(&a.length()).unwrap()),
+        Sort::ValueAsc => items.sort_by(|a, b| a.length().partial_cmp(&b.length()).unwrap()),
+        Sort::Name => items.sort_by(|a, b| a.name.cmp(b.name)),
+    }
+
+    let limit = match limit {
+        Some(n) if n < items.len() => n,
+        _ => return,
+    };
+
+    let rest = items.split_off(limit);
+    if let Some(other) = other {
+        items.push(Item {
+            value: rest.iter().map(Item::length).sum(),
+            ..other
+        });
+    }
+}
+
+fn parse_value(value: &Value, path: &str, value_type: ValueType, unit: f64)
+    -> Result<f64, Error>
+{
+    match (value, value_type) {
+        (Value::Number(t), _) => Ok(t.as_f64().unwrap() * unit),
+        (Value::String(s), ValueType::Duration) => {
+            time::parse_duration(s).ok_or_else(|| Error::InvalidDuration(s.clone()))
+        }
+        (Value::String(s), ValueType::Date) => {
+            time::parse_date(s).ok_or_else(|| Error::InvalidDate(s.clone()))
+        }
+        _ => Err(Error::InvalidValueType(path.to_string(), "Number")),
+    }
+}
+
+fn parse_reference_lines<'a>(list: &'a [Value], value_type: ValueType, unit: f64,
+                             default_color: Color)
+    -> Result<Vec<ReferenceLine<'a>>, Error>
+{
+    let mut lines = Vec::new();
+    for (i, obj) in list.iter().enumerate() {
+        let path = format!("/reference_lines/{}", i);
+        let value = match obj.get("value") {
+            Some(v) => parse_value(v, &format!("{}/value", path), value_type, unit)?,
+            None => return Err(Error::InvalidValue(format!("{}/value", path), "set")),
+        };
+
+        lines.push(ReferenceLine {
+            value,
+            label: get_str!(obj, "label"),
+            color: match get_str!(obj, "color") {
+                Some(s) => parse_color(s, &format!("{}/color", path))?,
+                None => default_color,
+            },
+        });
+    }
+
+    Ok(lines)
+}
+
+fn parse_bands<'a>(list: &'a [Value], value_type: ValueType, unit: f64, default_color: Color)
+    -> Result<Vec<Band<'a>>, Error>
+{
+    let mut bands = Vec::new();
+    for (i, obj) in list.iter().enumerate() {
+        let path = format!("/bands/{}", i);
+        let (from, to) = match (obj.get("from"), obj.get("to")) {
+            (Some(from), Some(to)) => {
+                (parse_value(from, &format!("{}/from", path), value_type, unit)?,
+                 parse_value(to, &format!("{}/to", path), value_type, unit)?)
+            }
+            _ => return Err(Error::InvalidValue(path, "an object with 'from' and 'to'")),
+        };
+
+        if to < from {
+            return Err(Error::InvalidValue(format!("{}/to", path), "not less than from"));
+        }
+
+        bands.push(Band {
+            from,
+            to,
+            label: get_str!(obj, "label"),
+            color: match get_str!(obj, "color") {
+                Some(s) => parse_color(s, &format!("{}/color", path))?,
+                None => default_color,
+            },
+        });
+    }
+
+    Ok(bands)
+}
+
+fn parse_theme(value: &Value) -> Result<Theme<'_>, Error> {
+    match *value {
+        Value::String(ref name) => Ok(Theme::by_name(name).unwrap_or_default()),
+        Value::Object(ref obj) => {
+            let mut theme = match get_str!(obj, "base") {
+                Some(name) => Theme::by_name(name).unwrap_or_default(),
+                None => Theme::default(),
+            };
+
+            for (name, v) in obj {
+                theme.set_field(name, v);
+            }
+
+            check_paint(theme.background, "/theme/background")?;
+            for &(path, color) in theme.colors().iter() {
+                parse_color(color, path)?;
+            }
+
+            Ok(theme)
+        }
+        _ => Ok(Theme::default()),
+    }
+}
+
+fn parse_plot_area(value: &Value) -> Result<PlotArea<'_>, Error> {
+    let mut pa = PlotArea::default();
+
+    if let Some(color) = get_str!(value, "background") {
+        check_paint(color, "/plot_area/background")?;
+        pa.background = color;
+    }
+
+    if let Some(color) = get_str!(value, "border") {
+        parse_color(color, "/plot_area/border")?;
+        pa.border = Some(color);
+    }
+
+    if let Some(n) = value.get("border_width").and_then(|v| v.as_u64()) {
+        pa.border_width = n as u32;
+    }
+
+    if let Some(n) = value.get("radius").and_then(|v| v.as_u64()) {
+        pa.radius = n as u32;
+    }
+
+    Ok(pa)
+}
+
+/// Applies an annotation setting over the `base` one.
+fn parse_annotation<'a>(value: &'a Value, base: Annotation<'a>) -> Annotation<'a> {
+    let mut a = base;
+    match *value {
+        Value::String(ref s) => a.show = s == "show",
+        Value::Object(ref obj) => {
+            if let Some(show) = value_option!(obj, "show", Bool) {
+                a.show = *show;
+            }
+
+            a.placement = match get_str!(obj, "placement") {
+                Some("auto") => Placement::Auto,
+                Some("inside_end") => Placement::InsideEnd,
+                Some("inside_start") => Placement::InsideStart,
+                Some("outside") => Placement::Outside,
+                Some("center") => Placement::Center,
+                _ => a.placement,
+            };
+
+            if let Some(text) = get_str!(obj, "template") {
+                a.template = Some(text);
+            }
+        }
+        _ => {}
+    }
+
+    a
+}
+
+fn parse_color(text: &str, path: &str) -> Result<Color, Error> {
+    color::parse(text).ok_or_else(|| Error::InvalidColor(path.to_string(), text.to_string()))
+}
+
+/// Checks a color, which can also be `none`.
+fn check_paint(text: &str, path: &str) -> Result<(), Error> {
+    if text != "none" {
+        parse_color(text, path)?;
+    }
+
+    Ok(())
+}
+
+/// Converts axis values from the value unit into seconds.
+fn scale_hor_axis(ha: &mut HorAxis, unit: f64) {
+    ha.min_value = ha.min_value.map(|n| n * unit);
+    ha.max_value = ha.max_value.map(|n| n * unit);
+    ha.tick_step = ha.tick_step.map(|n| n * unit);
+    if let Some(ref mut ticks) = ha.ticks {
+        for n in ticks.iter_mut() {
+            *n *= unit;
+        }
+    }
+}
+
 fn parse_font(value: &JSMap, name: &str, system_font: &FontData) -> Result<FontData, Error> {
     match value_option!(value, name, Object) {
         Some(font_obj) => {
@@ -200,7 +862,10 @@ fn parse_font(value: &JSMap, name: &str, system_font: &FontData) -> Result<FontD
                                 return Err(Error::CouldNotResolveFontPath);
                             }
                         }
-                        None => return Err(Error::InvalidValueType("/*_font/family", "String")),
+                        None => {
+                            let path = format!("/{}/family", name);
+                            return Err(Error::InvalidValueType(path, "String"));
+                        }
                     }
                 },
                 None => &system_font.family,
@@ -216,7 +881,7 @@ fn parse_font(value: &JSMap, name: &str, system_font: &FontData) -> Result<FontD
             Ok(FontData {
                 family: family.to_string(),
                 size: size as u8,
-                path: path,
+                path,
             })
         }
         None => {
@@ -225,10 +890,10 @@ fn parse_font(value: &JSMap, name: &str, system_font: &FontData) -> Result<FontD
     }
 }
 
-fn parse_hor_axis(value: &Value) -> Result<HorAxis, Error> {
+fn parse_hor_axis(value: &Value) -> Result<HorAxis<'_>, Error> {
     let ha = match value.as_object() {
         Some(c) => c,
-        None => return Err(Error::InvalidValueType("/hor_axis", "Map")),
+        None => return Err(Error::InvalidValueType("/hor_axis".to_string(), "Map")),
     };
 
     let ticks = match ha.get("ticks") {
@@ -248,43 +913,154 @@ fn parse_hor_axis(value: &Value) -> Result<HorAxis, Error> {
     };
 
     let width = match ha.get("width") {
-        Some(w) => {
-            match w.as_u64() {
-                Some(v) => Some(v as u32),
-                None => None,
-            }
+        Some(w) => w.as_u64().map(|v| v as u32),
+        None => None,
+    };
+
+    let tick_count = match ha.get("tick_count").and_then(|v| v.as_u64()) {
+        Some(n) if n < 2 => {
+            return Err(Error::InvalidValue("/hor_axis/tick_count".to_string(), "at least 2"));
         }
+        Some(n) => Some(n as u32),
         None => None,
     };
 
-    // TODO: this macros didn't check value types, which is bad
+    let tick_step = match get_num!(ha, "tick_step") {
+        Some(n) if n <= 0.0 => {
+            return Err(Error::InvalidValue("/hor_axis/tick_step".to_string(), "positive"));
+        }
+        n => n,
+    };
+
+    let scale = match get_str!(ha, "scale") {
+        Some("log") => {
+            match get_num!(ha, "log_base") {
+                Some(n) if n <= 1.0 => {
+                    let path = "/hor_axis/log_base".to_string();
+                    return Err(Error::InvalidValue(path, "greater than 1"));
+                }
+                Some(n) => Scale::Log(n),
+                None => Scale::Log(10.0),
+            }
+        }
+        _ => Scale::Linear,
+    };
+
+    let min_value = get_num!(ha, "min_value");
+    let max_value = get_num!(ha, "max_value");
+    if let (Some(min), Some(max)) = (min_value, max_value) {
+        if min >= max {
+            let path = "/hor_axis/min_value".to_string();
+            return Err(Error::InvalidValue(path, "less than max_value"));
+        }
+    }
+
+    // a linear axis starts at zero by default
+    if let (Scale::Linear, None, Some(max)) = (scale, min_value, max_value) {
+        if max <= 0.0 {
+            return Err(Error::InvalidValue("/hor_axis/max_value".to_string(), "greater than zero"));
+        }
+    }
+
+    if let (Scale::Log(_), Some(min)) = (scale, min_value) {
+        if min <= 0.0 {
+            return Err(Error::InvalidValue("/hor_axis/min_value".to_string(),
+                                           "greater than zero on a logarithmic scale"));
+        }
+    }
+
     Ok(HorAxis {
         title: get_str!(ha, "title"),
         suffix: get_str!(ha, "suffix"),
-        max_value: get_num!(ha, "max_value"),
-        ticks: ticks,
-        width: width,
-        round_tick_values: *value_option!(ha, "round_tick_values", Bool).unwrap_or(&false)
+        min_value,
+        max_value,
+        ticks,
+        width,
+        round_tick_values: *value_option!(ha, "round_tick_values", Bool).unwrap_or(&false),
+        tick_count,
+        tick_step,
+        scale,
+        axis_break: *value_option!(ha, "axis_break", Bool).unwrap_or(&false),
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use super::DEFAULT_BAR_COLOR;
-    use font::FontData;
-    use serde_json;
+fn parse_number_format(value: &Value) -> Result<NumberFormat<'_>, Error> {
+    let obj = match value.as_object() {
+        Some(o) => o,
+        None => return Err(Error::InvalidValueType("/number_format".to_string(), "Object")),
+    };
 
-    static DEFAULT_ITEM: &'static Item<'static> = &Item {
-        name: "some name",
-        value: 42.0,
-        color: "#3260cd", // we can't use DEFAULT_BAR_COLOR here
+    let precision = match (get_num!(obj, "decimals"), get_num!(obj, "significant")) {
+        (Some(_), Some(_)) => {
+            return Err(Error::InvalidValue("/number_format".to_string(),
+                                           "set either 'decimals' or 'significant'"));
+        }
+        (Some(n), None) => Precision::Decimals(n.min(20.0) as u8),
+        (None, Some(n)) if n < 1.0 => {
+            return Err(Error::InvalidValue("/number_format/significant".to_string(), "at least 1"));
+        }
+        (None, Some(n)) => Precision::Significant(n.min(20.0) as u8),
+        (None, None) => Precision::Auto,
     };
 
-    macro_rules! test {
-        ($name:ident, $data:expr, $result:expr) => (
-            #[test]
-            fn $name() {
+    let (mut decimal_mark, mut separator) = match get_str!(obj, "locale") {
+        Some(locale) => {
+            match number_format::locale_marks(locale) {
+                Some(marks) => marks,
+                None => {
+                    return Err(Error::InvalidValue("/number_format/locale".to_string(),
+                        "one of: en, de, es, it, nl, pt, fr, ru, pl, cs, sv, ch"));
+                }
+            }
+        }
+        None => (".", ","),
+    };
+
+    if let Some(s) = get_str!(obj, "decimal_mark") {
+        decimal_mark = s;
+    }
+
+    if let Some(s) = get_str!(obj, "thousands_separator") {
+        separator = s;
+    }
+
+    let grouping = *value_option!(obj, "grouping", Bool).unwrap_or(&false)
+                   || obj.contains_key("thousands_separator");
+
+    Ok(NumberFormat {
+        precision,
+        decimal_mark,
+        thousands_separator: if grouping { Some(separator) } else { None },
+        si: *value_option!(obj, "si", Bool).unwrap_or(&false),
+        percent: *value_option!(obj, "percent", Bool).unwrap_or(&false),
+        prefix: get_str!(obj, "prefix").unwrap_or(""),
+        suffix: get_str!(obj, "suffix").unwrap_or(""),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use font::FontData;
+    use serde_json;
+
+    // the default theme bar color
+    static DEFAULT_BAR_COLOR: Color = Color { red: 0x32, green: 0x60, blue: 0xcd };
+
+    static DEFAULT_ITEM: &Item<'static> = &Item {
+        name: "some name",
+        start: None,
+        value: 42.0,
+        error: None,
+        target: None,
+        color: DEFAULT_BAR_COLOR,
+        annotation: Annotation { show: true, placement: Placement::Auto, template: None },
+    };
+
+    macro_rules! test {
+        ($name:ident, $data:expr, $result:expr) => (
+            #[test]
+            fn $name() {
                 let value: serde_json::Value = serde_json::from_slice($data).unwrap();
                 let sf = FontData::system_font().unwrap();
                 assert_eq!(Config::from_value(&value, &sf).unwrap(), $result);
@@ -305,7 +1081,8 @@ mod tests {
 
     test_err!(empty_1, b"{}", Error::ItemsIsNotSet);
     test_err!(empty_2, b"{ \"items\": [] }", Error::ItemsIsNotSet);
-    test_err!(invalid_item_1, b"{ \"items\": [ {} ] }", Error::ItemNameIsNotSet);
+    test_err!(invalid_item_1, b"{ \"items\": [ {} ] }",
+        Error::ItemNameIsNotSet("/items/0".to_string()));
 
     test_err!(invalid_item_2,
         b"{
@@ -315,7 +1092,7 @@ mod tests {
                 }
             ]
         }",
-        Error::ItemValueIsNotSet);
+        Error::ItemValueIsNotSet("/items/0".to_string()));
 
     test_err!(invalid_item_3,
         b"{
@@ -326,7 +1103,7 @@ mod tests {
                 }
             ]
         }",
-        Error::InvalidValueType("/items/n/value", "Number"));
+        Error::InvalidValueType("/items/0/value".to_string(), "Number"));
 
     test_err!(invalid_item_4,
         b"{
@@ -337,7 +1114,17 @@ mod tests {
                 }
             ]
         }",
-        Error::VelueMustBePositive);
+        Error::ValueMustBePositive("/items/0/value".to_string()));
+
+    test_err!(invalid_item_5,
+        b"{
+            \"items\": [
+                { \"name\": \"a\", \"value\": 1 },
+                { \"name\": \"b\", \"value\": 2 },
+                { \"name\": 3, \"value\": 3 }
+            ]
+        }",
+        Error::InvalidValueType("/items/2/name".to_string(), "String"));
 
     test!(minimal_1,
         b"{
@@ -371,8 +1158,12 @@ mod tests {
                 *DEFAULT_ITEM,
                 Item {
                     name: "other name",
+                    start: None,
                     value: 142.5,
+                    error: None,
+                    target: None,
                     color: DEFAULT_BAR_COLOR,
+                    annotation: Annotation::default(),
                 }
             ],
             ..Config::default()
@@ -396,13 +1187,21 @@ mod tests {
             items: vec![
                 Item {
                     name: "some name",
+                    start: None,
                     value: 42.0,
-                    color: "red",
+                    error: None,
+                    target: None,
+                    color: Color::new(255, 0, 0),
+                    annotation: Annotation::default(),
                 },
                 Item {
                     name: "other name",
+                    start: None,
                     value: 142.5,
+                    error: None,
+                    target: None,
                     color: DEFAULT_BAR_COLOR,
+                    annotation: Annotation::default(),
                 }
             ],
             ..Config::default()
@@ -549,4 +1348,698 @@ mod tests {
             }),
             ..Config::default()
         });
+
+    test_err!(haxis_width_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"width\": \"400\"
+            }
+        }",
+        Error::InvalidValueType("/hor_axis/width".to_string(), "UInt"));
+
+    test_err!(haxis_ticks_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"ticks\": [10, \"20\"]
+            }
+        }",
+        Error::InvalidValueType("/hor_axis/ticks/1".to_string(), "Number"));
+
+    test!(haxis_tick_count_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"tick_count\": 7,
+                \"tick_step\": 2.5
+            }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            hor_axis: Some(HorAxis {
+                tick_count: Some(7),
+                tick_step: Some(2.5),
+                ..HorAxis::default()
+            }),
+            ..Config::default()
+        });
+
+    test_err!(haxis_tick_count_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"tick_count\": 1
+            }
+        }",
+        Error::InvalidValue("/hor_axis/tick_count".to_string(), "at least 2"));
+
+    test!(number_format_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"number_format\": {
+                \"significant\": 2,
+                \"si\": true,
+                \"locale\": \"de\",
+                \"grouping\": true,
+                \"suffix\": \" ms\"
+            }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            number_format: NumberFormat {
+                precision: Precision::Significant(2),
+                decimal_mark: ",",
+                thousands_separator: Some("."),
+                si: true,
+                suffix: " ms",
+                ..NumberFormat::default()
+            },
+            ..Config::default()
+        });
+
+    test_err!(number_format_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"number_format\": {
+                \"significant\": 2,
+                \"decimals\": 2
+            }
+        }",
+        Error::InvalidValue("/number_format".to_string(),
+                            "set either 'decimals' or 'significant'"));
+
+    test!(haxis_scale_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"scale\": \"log\",
+                \"log_base\": 2
+            }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            hor_axis: Some(HorAxis {
+                scale: Scale::Log(2.0),
+                ..HorAxis::default()
+            }),
+            ..Config::default()
+        });
+
+    test_err!(haxis_max_value_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"max_value\": 0
+            }
+        }",
+        Error::InvalidValue("/hor_axis/max_value".to_string(), "greater than zero"));
+
+    test_err!(haxis_max_value_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"max_value\": -10
+            }
+        }",
+        Error::InvalidValue("/hor_axis/max_value".to_string(), "greater than zero"));
+
+    test_err!(haxis_scale_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"scale\": \"exp\"
+            }
+        }",
+        Error::InvalidVariant("/hor_axis/scale".to_string(), &["linear", "log"]));
+
+    test_err!(haxis_scale_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 0}],
+            \"hor_axis\": {
+                \"scale\": \"log\"
+            }
+        }",
+        Error::ZeroValueOnLogScale("/items/0/value".to_string()));
+
+    test_err!(haxis_scale_4,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42, \"target\": 0}],
+            \"hor_axis\": {
+                \"scale\": \"log\"
+            }
+        }",
+        Error::ZeroValueOnLogScale("/items/0/target".to_string()));
+
+    test_err!(haxis_scale_5,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"reference_lines\": [{ \"value\": 0}],
+            \"hor_axis\": {
+                \"scale\": \"log\"
+            }
+        }",
+        Error::ZeroValueOnLogScale("/reference_lines/0/value".to_string()));
+
+    test_err!(haxis_scale_6,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"bands\": [{ \"from\": 0, \"to\": 10}],
+            \"hor_axis\": {
+                \"scale\": \"log\"
+            }
+        }",
+        Error::ZeroValueOnLogScale("/bands/0/from".to_string()));
+
+    test!(haxis_min_value_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"min_value\": 40,
+                \"axis_break\": true
+            }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            hor_axis: Some(HorAxis {
+                min_value: Some(40.0),
+                axis_break: true,
+                ..HorAxis::default()
+            }),
+            ..Config::default()
+        });
+
+    test_err!(haxis_min_value_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"hor_axis\": {
+                \"min_value\": 50,
+                \"max_value\": 50
+            }
+        }",
+        Error::InvalidValue("/hor_axis/min_value".to_string(), "less than max_value"));
+
+    test!(value_type_1,
+        b"{
+            \"value_type\": \"duration\",
+            \"value_unit\": \"ms\",
+            \"items\": [
+                { \"name\": \"item 1\",\"value\": \"1.5s\"},
+                { \"name\": \"item 2\",\"value\": 250}
+            ]
+        }",
+        Config {
+            items: vec![
+                Item { name: "item 1", value: 1.5, ..*DEFAULT_ITEM },
+                Item { name: "item 2", value: 0.25, ..*DEFAULT_ITEM },
+            ],
+            value_type: ValueType::Duration,
+            ..Config::default()
+        });
+
+    test!(value_type_2,
+        b"{
+            \"value_type\": \"date\",
+            \"items\": [{ \"name\": \"some name\",\"value\": \"1970-01-02\"}]
+        }",
+        Config {
+            items: vec![Item { value: 86400.0, ..*DEFAULT_ITEM }],
+            value_type: ValueType::Date,
+            ..Config::default()
+        });
+
+    test_err!(value_type_3,
+        b"{
+            \"value_type\": \"duration\",
+            \"items\": [{ \"name\": \"some name\",\"value\": \"2 weeks\"}]
+        }",
+        Error::InvalidDuration("2 weeks".to_string()));
+
+    test_err!(value_type_4,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": \"1.5s\"}]
+        }",
+        Error::InvalidValueType("/items/0/value".to_string(), "Number"));
+
+    test!(range_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"start\": 10, \"end\": 42}]
+        }",
+        Config {
+            items: vec![Item { start: Some(10.0), ..*DEFAULT_ITEM }],
+            ..Config::default()
+        });
+
+    test_err!(range_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"start\": 10}]
+        }",
+        Error::ItemRangeIsIncomplete("/items/0".to_string()));
+
+    test_err!(range_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"start\": 50, \"end\": 42}]
+        }",
+        Error::InvalidValue("/items/0/end".to_string(), "not less than start"));
+
+    test!(error_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42, \"error\": 2, \"error_high\": 5}]
+        }",
+        Config {
+            items: vec![Item { error: Some((2.0, 5.0)), ..*DEFAULT_ITEM }],
+            ..Config::default()
+        });
+
+    test_err!(error_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42, \"error_low\": -1}]
+        }",
+        Error::InvalidValue("/items/0/error".to_string(), "positive"));
+
+    test!(reference_lines_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42, \"target\": 50}],
+            \"reference_lines\": [{ \"value\": 40, \"label\": \"SLA\"}],
+            \"bands\": [{ \"from\": 10, \"to\": 20, \"color\": \"#eee\"}]
+        }",
+        Config {
+            items: vec![Item { target: Some(50.0), ..*DEFAULT_ITEM }],
+            reference_lines: vec![ReferenceLine {
+                value: 40.0,
+                label: Some("SLA"),
+                color: Color::new(0xd6, 0x27, 0x28),
+            }],
+            bands: vec![Band {
+                from: 10.0,
+                to: 20.0,
+                label: None,
+                color: Color::new(0xee, 0xee, 0xee),
+            }],
+            ..Config::default()
+        });
+
+    test_err!(reference_lines_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"bands\": [{ \"from\": 20, \"to\": 10}]
+        }",
+        Error::InvalidValue("/bands/0/to".to_string(), "not less than from"));
+
+    test!(sort_1,
+        b"{
+            \"items\": [
+                { \"name\": \"b\",\"value\": 1},
+                { \"name\": \"a\",\"value\": 3},
+                { \"name\": \"c\",\"value\": 2},
+                { \"name\": \"d\",\"value\": 0.5}
+            ],
+            \"sort\": \"value_desc\",
+            \"limit\": 2,
+            \"other\": \"Other\"
+        }",
+        Config {
+            items: vec![
+                Item { name: "a", value: 3.0, ..*DEFAULT_ITEM },
+                Item { name: "c", value: 2.0, ..*DEFAULT_ITEM },
+                Item {
+                    name: "Other",
+                    value: 1.5,
+                    color: Color::new(0x99, 0x99, 0x99),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            ..Config::default()
+        });
+
+    test!(sort_2,
+        b"{
+            \"items\": [
+                { \"name\": \"b\",\"value\": 1},
+                { \"name\": \"a\",\"value\": 3},
+                { \"name\": \"c\",\"value\": 2}
+            ],
+            \"sort\": \"name\",
+            \"limit\": 2
+        }",
+        Config {
+            items: vec![
+                Item { name: "a", value: 3.0, ..*DEFAULT_ITEM },
+                Item { name: "b", value: 1.0, ..*DEFAULT_ITEM },
+            ],
+            ..Config::default()
+        });
+
+    test!(sort_3,
+        b"{
+            \"items\": [
+                { \"name\": \"a\",\"start\": 8, \"end\": 10},
+                { \"name\": \"b\",\"start\": 1, \"end\": 5},
+                { \"name\": \"c\",\"start\": 2, \"end\": 9}
+            ],
+            \"sort\": \"value_desc\",
+            \"limit\": 1,
+            \"other\": \"Other\"
+        }",
+        Config {
+            items: vec![
+                Item { name: "c", start: Some(2.0), value: 9.0, ..*DEFAULT_ITEM },
+                Item {
+                    name: "Other",
+                    value: 6.0,
+                    color: Color::new(0x99, 0x99, 0x99),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            ..Config::default()
+        });
+
+    test!(groups_1,
+        b"{
+            \"groups\": [
+                { \"name\": \"parser\", \"items\": [{ \"name\": \"some name\",\"value\": 42}] },
+                { \"name\": \"writer\", \"items\": [{ \"name\": \"some name\",\"value\": 42}] }
+            ],
+            \"group_separator\": true
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM, *DEFAULT_ITEM],
+            groups: vec![
+                Group { name: "parser", start: 0, len: 1 },
+                Group { name: "writer", start: 1, len: 1 },
+            ],
+            group_separator: true,
+            ..Config::default()
+        });
+
+    test!(groups_limit_1,
+        b"{
+            \"groups\": [
+                { \"name\": \"parser\", \"items\": [
+                    { \"name\": \"a\", \"value\": 1 },
+                    { \"name\": \"b\", \"value\": 3 },
+                    { \"name\": \"c\", \"value\": 2 }
+                ]},
+                { \"name\": \"writer\", \"items\": [
+                    { \"name\": \"d\", \"value\": 5 },
+                    { \"name\": \"e\", \"value\": 4 }
+                ]}
+            ],
+            \"sort\": \"value_desc\",
+            \"limit\": 1,
+            \"other\": \"Other\"
+        }",
+        Config {
+            items: vec![
+                Item { name: "b", value: 3.0, ..*DEFAULT_ITEM },
+                Item {
+                    name: "Other",
+                    value: 3.0,
+                    color: Color::new(0x99, 0x99, 0x99),
+                    ..*DEFAULT_ITEM
+                },
+                Item { name: "d", value: 5.0, ..*DEFAULT_ITEM },
+                Item {
+                    name: "Other",
+                    value: 4.0,
+                    color: Color::new(0x99, 0x99, 0x99),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            groups: vec![
+                Group { name: "parser", start: 0, len: 2 },
+                Group { name: "writer", start: 2, len: 2 },
+            ],
+            ..Config::default()
+        });
+
+    test_err!(groups_2,
+        b"{
+            \"groups\": [{ \"name\": \"parser\", \"items\": [] }]
+        }",
+        Error::InvalidValue("/groups/0/items".to_string(), "a non-empty array"));
+
+    test_err!(groups_3,
+        b"{
+            \"groups\": [
+                { \"name\": \"parser\", \"items\": [{ \"name\": \"a\", \"value\": 1 }] },
+                { \"name\": \"writer\", \"items\": [
+                    { \"name\": \"a\", \"value\": 1 },
+                    { \"name\": \"b\", \"value\": 2, \"color\": \"#32g0cd\" }
+                ]}
+            ]
+        }",
+        Error::InvalidColor("/groups/1/items/1/color".to_string(), "#32g0cd".to_string()));
+
+    test!(theme_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"theme\": { \"base\": \"dark\", \"bar_color\": \"#3260cd\" }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            theme: Theme {
+                bar_color: "#3260cd",
+                ..Theme::by_name("dark").unwrap()
+            },
+            ..Config::default()
+        });
+
+    test_err!(theme_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"theme\": \"light\"
+        }",
+        Error::InvalidVariant("/theme".to_string(), &["default", "minimal", "dark", "print"]));
+
+    test!(plot_area_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"background\": \"#fff\",
+            \"padding\": 10,
+            \"plot_area\": { \"background\": \"#eee\", \"border\": \"#ccc\", \"radius\": 4 }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            theme: Theme {
+                background: "#fff",
+                ..Theme::default()
+            },
+            plot_area: PlotArea {
+                background: "#eee",
+                border: Some("#ccc"),
+                border_width: 1,
+                radius: 4,
+            },
+            padding: 10,
+            ..Config::default()
+        });
+
+    test!(palette_1,
+        b"{
+            \"items\": [
+                { \"name\": \"item1\", \"value\": 1 },
+                { \"name\": \"item2\", \"value\": 2, \"color\": \"#000\" },
+                { \"name\": \"item3\", \"value\": 3 }
+            ],
+            \"palette\": \"google\"
+        }",
+        Config {
+            items: vec![
+                Item {
+                    name: "item1",
+                    value: 1.0,
+                    color: Color::new(0x33, 0x66, 0xcc),
+                    ..*DEFAULT_ITEM
+                },
+                Item { name: "item2", value: 2.0, color: Color::new(0, 0, 0), ..*DEFAULT_ITEM },
+                Item {
+                    name: "item3",
+                    value: 3.0,
+                    color: Color::new(0xff, 0x99, 0),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            ..Config::default()
+        });
+
+    test!(palette_2,
+        b"{
+            \"items\": [
+                { \"name\": \"c\", \"value\": 2 },
+                { \"name\": \"a\", \"value\": 1 }
+            ],
+            \"palette\": [\"#000\", \"#111\", \"#222\"],
+            \"color_by\": \"name\",
+            \"sort\": \"name\"
+        }",
+        Config {
+            items: vec![
+                Item {
+                    name: "a",
+                    value: 1.0,
+                    color: Color::new(0x11, 0x11, 0x11),
+                    ..*DEFAULT_ITEM
+                },
+                Item {
+                    name: "c",
+                    value: 2.0,
+                    color: Color::new(0x22, 0x22, 0x22),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            ..Config::default()
+        });
+
+    test!(palette_5,
+        b"{
+            \"groups\": [
+                { \"name\": \"parser\", \"items\": [{ \"name\": \"a\", \"value\": 1 }] },
+                { \"name\": \"writer\", \"items\": [{ \"name\": \"b\", \"value\": 2 }] }
+            ],
+            \"items\": [{ \"name\": \"c\", \"value\": 3 }],
+            \"palette\": [\"#000\", \"#111\", \"#222\"]
+        }",
+        Config {
+            items: vec![
+                Item {
+                    name: "c",
+                    value: 3.0,
+                    color: Color::new(0x00, 0x00, 0x00),
+                    ..*DEFAULT_ITEM
+                },
+                Item {
+                    name: "a",
+                    value: 1.0,
+                    color: Color::new(0x11, 0x11, 0x11),
+                    ..*DEFAULT_ITEM
+                },
+                Item {
+                    name: "b",
+                    value: 2.0,
+                    color: Color::new(0x22, 0x22, 0x22),
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            groups: vec![
+                Group { name: "parser", start: 1, len: 1 },
+                Group { name: "writer", start: 2, len: 1 },
+            ],
+            ..Config::default()
+        });
+
+    test_err!(palette_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"palette\": []
+        }",
+        Error::InvalidValue("/palette".to_string(), "a non-empty array"));
+
+    test!(color_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\", \"value\": 42, \"color\": \"hsl(0, 100%, 50%)\"}]
+        }",
+        Config {
+            items: vec![Item { color: Color::new(255, 0, 0), ..*DEFAULT_ITEM }],
+            ..Config::default()
+        });
+
+    test_err!(color_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\", \"value\": 42, \"color\": \"#32g0cd\"}]
+        }",
+        Error::InvalidColor("/items/0/color".to_string(), "#32g0cd".to_string()));
+
+    test_err!(palette_4,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"palette\": [\"#000\", \"#32g0cd\"]
+        }",
+        Error::InvalidColor("/palette/1".to_string(), "#32g0cd".to_string()));
+
+    test_err!(reference_lines_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"reference_lines\": [{ \"value\": 20}, { \"value\": 30, \"color\": \"#32g0cd\"}]
+        }",
+        Error::InvalidColor("/reference_lines/1/color".to_string(), "#32g0cd".to_string()));
+
+    test_err!(color_3,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"theme\": { \"tick_color\": \"grey50\" }
+        }",
+        Error::InvalidColor("/theme/tick_color".to_string(), "grey50".to_string()));
+
+    test!(annotation_1,
+        b"{
+            \"items\": [
+                { \"name\": \"some name\", \"value\": 42 },
+                { \"name\": \"some name\", \"value\": 42, \"annotation\": \"show\" },
+                {
+                    \"name\": \"some name\",
+                    \"value\": 42,
+                    \"annotation\": { \"placement\": \"center\", \"template\": \"n/a\" }
+                }
+            ],
+            \"annotation\": \"hide\"
+        }",
+        Config {
+            items: vec![
+                Item {
+                    annotation: Annotation { show: false, ..Annotation::default() },
+                    ..*DEFAULT_ITEM
+                },
+                *DEFAULT_ITEM,
+                Item {
+                    annotation: Annotation {
+                        show: false,
+                        placement: Placement::Center,
+                        template: Some("n/a"),
+                    },
+                    ..*DEFAULT_ITEM
+                },
+            ],
+            ..Config::default()
+        });
+
+    test_err!(annotation_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"annotation\": { \"placement\": \"left\" }
+        }",
+        Error::InvalidVariant("/annotation/placement".to_string(),
+                              &["auto", "inside_end", "inside_start", "outside", "center"]));
+
+    test!(orientation_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"orientation\": \"vertical\"
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            orientation: Orientation::Vertical,
+            ..Config::default()
+        });
+
+    test_err!(orientation_2,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"orientation\": \"diagonal\"
+        }",
+        Error::InvalidVariant("/orientation".to_string(), &["horizontal", "vertical"]));
+
+    test!(accessibility_1,
+        b"{
+            \"items\": [{ \"name\": \"some name\",\"value\": 42}],
+            \"accessibility\": { \"summary\": \"Only one item.\", \"data_table\": true }
+        }",
+        Config {
+            items: vec![*DEFAULT_ITEM],
+            accessibility: Accessibility {
+                summary: Some("Only one item."),
+                data_table: true,
+            },
+            ..Config::default()
+        });
 }
@@ -0,0 +1,296 @@
+use std::error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use csv;
+use serde_json::{Map, Number, Value};
+
+use super::{BarhResult, load_file};
+use schema;
+
+/// A CSV column reference.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    /// Detected automatically when not set.
+    pub has_header: Option<bool>,
+    pub name_column: Column,
+    /// Only the first column is used for now.
+    pub value_columns: Vec<Column>,
+}
+
+impl CsvOptions {
+    /// Creates options which use the first column as names and the second one as values.
+    pub fn new(delimiter: u8) -> CsvOptions {
+        CsvOptions {
+            delimiter,
+            has_header: None,
+            name_column: Column::Index(0),
+            value_columns: vec![Column::Index(1)],
+        }
+    }
+
+    /// Creates options with a delimiter based on the file extension.
+    pub fn from_path(path: &str) -> CsvOptions {
+        let is_tsv = Path::new(path).extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("tsv"))
+            .unwrap_or(false);
+
+        CsvOptions::new(if is_tsv { b'\t' } else { b',' })
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Error {
+    CsvIsNotSet,
+    ColumnNotFound(String),
+    NoValueColumn,
+    InvalidDelimiter,
+    MissingField(u64, usize), // line, column
+    InvalidNumber(u64, String), // line, text
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::CsvIsNotSet => write!(f, "'/data/csv' is not set"),
+            Error::ColumnNotFound(ref name) => write!(f, "column '{}' not found", name),
+            Error::NoValueColumn => write!(f, "'/data/value_columns' is empty"),
+            Error::InvalidDelimiter => {
+                write!(f, "'/data/delimiter' must be a single ASCII character")
+            }
+            Error::MissingField(line, column) => {
+                write!(f, "line {}: column {} is missing", line, column)
+            }
+            Error::InvalidNumber(line, ref text) => {
+                write!(f, "line {}: '{}' is not a number", line, text)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Converts CSV data into a list of config items.
+pub fn parse_csv(data: &[u8], opt: &CsvOptions) -> BarhResult<Vec<Value>> {
+    let value_column = match opt.value_columns.first() {
+        Some(c) => c,
+        None => return Err(Error::NoValueColumn.into()),
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(opt.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data);
+
+    let mut records = Vec::new();
+    for rec in reader.records() {
+        records.push(rec?);
+    }
+
+    let has_header = match opt.has_header {
+        Some(flag) => flag,
+        None => {
+            // columns can be referenced by name only when the header is present
+            let is_named = |c: &Column| match *c {
+                Column::Name(_) => true,
+                Column::Index(_) => false,
+            };
+
+            if is_named(&opt.name_column) || is_named(value_column) {
+                true
+            } else {
+                // the first row is a header when it doesn't have a value
+                match (records.first(), value_column) {
+                    (Some(rec), &Column::Index(idx)) => {
+                        rec.get(idx).map(|s| s.trim().parse::<f64>().is_err()).unwrap_or(false)
+                    }
+                    _ => false,
+                }
+            }
+        }
+    };
+
+    let (header, rows) = if has_header && !records.is_empty() {
+        (Some(&records[0]), &records[1..])
+    } else {
+        (None, &records[..])
+    };
+
+    let find_column = |c: &Column| -> Result<usize, Error> {
+        match *c {
+            Column::Index(idx) => Ok(idx),
+            Column::Name(ref name) => {
+                header.and_then(|h| h.iter().position(|s| s.trim() == name))
+                      .ok_or_else(|| Error::ColumnNotFound(name.clone()))
+            }
+        }
+    };
+
+    let name_idx = find_column(&opt.name_column)?;
+    let value_idx = find_column(value_column)?;
+
+    let mut items = Vec::new();
+    for rec in rows {
+        let line = rec.position().map(|p| p.line()).unwrap_or(0);
+
+        let name = match rec.get(name_idx) {
+            Some(s) => s,
+            None => return Err(Error::MissingField(line, name_idx).into()),
+        };
+
+        let text = match rec.get(value_idx) {
+            Some(s) => s.trim(),
+            None => return Err(Error::MissingField(line, value_idx).into()),
+        };
+
+        let value = match text.parse::<f64>().ok().and_then(Number::from_f64) {
+            Some(n) => n,
+            None => return Err(Error::InvalidNumber(line, text.to_string()).into()),
+        };
+
+        let mut item = Map::new();
+        item.insert("name".to_string(), Value::String(name.to_string()));
+        item.insert("value".to_string(), Value::Number(value));
+        items.push(Value::Object(item));
+    }
+
+    Ok(items)
+}
+
+/// Loads items referenced by the `data` block of the config.
+///
+/// Items are appended to `/items`. Relative paths are resolved against `base_dir`.
+pub fn resolve(conf: &mut Value, base_dir: &Path) -> BarhResult<()> {
+    let items = match conf.get("data") {
+        Some(data) => {
+            let obj = match data.as_object() {
+                Some(obj) => obj,
+                None => return Ok(()), // will be reported by Config::from_value
+            };
+
+            schema::validate(obj, schema::DATA_FIELDS)?;
+
+            let path = match source_path(conf, base_dir) {
+                Some(p) => p,
+                None => return Err(Error::CsvIsNotSet.into()),
+            };
+            let path = path.to_string_lossy();
+
+            let mut opt = CsvOptions::from_path(&path);
+
+            if let Some(s) = obj.get("delimiter").and_then(|v| v.as_str()) {
+                opt.delimiter = match s.as_bytes() {
+                    [c] if c.is_ascii() => *c,
+                    _ => return Err(Error::InvalidDelimiter.into()),
+                };
+            }
+
+            opt.has_header = obj.get("has_header").and_then(|v| v.as_bool());
+
+            if let Some(c) = obj.get("name_column") {
+                opt.name_column = parse_column(c);
+            }
+
+            if let Some(list) = obj.get("value_columns").and_then(|v| v.as_array()) {
+                opt.value_columns = list.iter().map(parse_column).collect();
+            }
+
+            parse_csv(&load_file(&path)?, &opt)?
+        }
+        None => return Ok(()),
+    };
+
+    if let Some(obj) = conf.as_object_mut() {
+        let list = obj.entry("items").or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(ref mut list) = *list {
+            list.extend(items);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a path to the file referenced by the `data` block.
+pub fn source_path(conf: &Value, base_dir: &Path) -> Option<PathBuf> {
+    conf.get("data")
+        .and_then(|data| data.get("csv"))
+        .and_then(|v| v.as_str())
+        .map(|p| base_dir.join(p))
+}
+
+fn parse_column(value: &Value) -> Column {
+    match *value {
+        Value::String(ref s) => Column::Name(s.clone()),
+        _ => Column::Index(value.as_u64().unwrap_or(0) as usize),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error;
+
+    fn names_and_values(items: &[Value]) -> Vec<(String, f64)> {
+        items.iter()
+             .map(|v| (v["name"].as_str().unwrap().to_string(), v["value"].as_f64().unwrap()))
+             .collect()
+    }
+
+    #[test]
+    fn csv_1() {
+        let data = b"item 1,20\nitem 2,50.5\n";
+        let items = parse_csv(data, &CsvOptions::new(b',')).unwrap();
+        assert_eq!(names_and_values(&items),
+                   vec![("item 1".to_string(), 20.0), ("item 2".to_string(), 50.5)]);
+    }
+
+    #[test]
+    fn csv_header_1() {
+        let data = b"name,time\n\"item, 1\",20\n";
+        let items = parse_csv(data, &CsvOptions::new(b',')).unwrap();
+        assert_eq!(names_and_values(&items), vec![("item, 1".to_string(), 20.0)]);
+    }
+
+    #[test]
+    fn csv_columns_1() {
+        let data = b"id\tname\tmin\tmax\n1\titem 1\t10\t20\n";
+        let mut opt = CsvOptions::new(b'\t');
+        opt.name_column = Column::Name("name".to_string());
+        opt.value_columns = vec![Column::Name("max".to_string())];
+        let items = parse_csv(data, &opt).unwrap();
+        assert_eq!(names_and_values(&items), vec![("item 1".to_string(), 20.0)]);
+    }
+
+    #[test]
+    fn csv_invalid_number_1() {
+        let data = b"name,time\nitem 1,20\nitem 2,n/a\n";
+        match parse_csv(data, &CsvOptions::new(b',')) {
+            Err(error::Error::DataError(e)) => {
+                assert_eq!(e, Error::InvalidNumber(3, "n/a".to_string()));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn csv_unknown_column_1() {
+        let data = b"name,time\nitem 1,20\n";
+        let mut opt = CsvOptions::new(b',');
+        opt.value_columns = vec![Column::Name("value".to_string())];
+        match parse_csv(data, &opt) {
+            Err(error::Error::DataError(e)) => {
+                assert_eq!(e, Error::ColumnNotFound("value".to_string()));
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -1,22 +1,60 @@
+use std::error;
+use std::fmt;
 use std::io;
 use std::num;
+use std::str;
 
+use csv;
 use serde_json;
+use serde_yaml;
+use toml;
 use config;
+use data;
 
 pub type BarhResult<T> = Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
-    IoError(io::Error),
+    IoError(String, io::Error), // file path or a stream name, error
     NumParseError(num::ParseIntError),
+    Utf8Error(str::Utf8Error),
     JsonError(serde_json::Error),
+    YamlError(serde_yaml::Error),
+    TomlError(toml::de::Error),
+    CsvError(csv::Error),
     ConfigError(config::Error),
+    DataError(data::Error),
 }
 
-impl From<io::Error> for Error {
-    fn from(value: io::Error) -> Error {
-        Error::IoError(value)
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::IoError(ref path, _) => write!(f, "failed to access '{}'", path),
+            Error::NumParseError(_) => write!(f, "failed to parse a number"),
+            Error::Utf8Error(_) => write!(f, "invalid UTF-8"),
+            Error::JsonError(_) => write!(f, "failed to parse JSON"),
+            Error::YamlError(_) => write!(f, "failed to parse YAML"),
+            Error::TomlError(_) => write!(f, "failed to parse TOML"),
+            Error::CsvError(_) => write!(f, "failed to parse CSV"),
+            Error::ConfigError(_) => write!(f, "invalid config"),
+            Error::DataError(_) => write!(f, "failed to load data"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::IoError(_, ref e) => Some(e),
+            Error::NumParseError(ref e) => Some(e),
+            Error::Utf8Error(ref e) => Some(e),
+            Error::JsonError(ref e) => Some(e),
+            Error::YamlError(ref e) => Some(e),
+            Error::TomlError(ref e) => Some(e),
+            Error::CsvError(ref e) => Some(e),
+            Error::ConfigError(ref e) => Some(e),
+            Error::DataError(ref e) => Some(e),
+        }
     }
 }
 
@@ -32,8 +70,72 @@ impl From<config::Error> for Error {
     }
 }
 
+impl From<str::Utf8Error> for Error {
+    fn from(value: str::Utf8Error) -> Error {
+        Error::Utf8Error(value)
+    }
+}
+
+impl From<data::Error> for Error {
+    fn from(value: data::Error) -> Error {
+        Error::DataError(value)
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(value: serde_json::Error) -> Error {
         Error::JsonError(value)
     }
 }
+
+impl From<serde_yaml::Error> for Error {
+    fn from(value: serde_yaml::Error) -> Error {
+        Error::YamlError(value)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(value: toml::de::Error) -> Error {
+        Error::TomlError(value)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(value: csv::Error) -> Error {
+        Error::CsvError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as StdError;
+
+    use super::*;
+    use serde_json;
+    use config;
+    use load_file;
+
+    #[test]
+    fn json_error_1() {
+        let e = serde_json::from_slice::<serde_json::Value>(b"{\n  \"items\": [,]\n}").unwrap_err();
+        let e = Error::from(e);
+        assert_eq!(e.to_string(), "failed to parse JSON");
+        assert_eq!(e.source().unwrap().to_string(), "expected value at line 2 column 13");
+    }
+
+    #[test]
+    fn io_error_1() {
+        let e = load_file("no/such/config.json").unwrap_err();
+        assert_eq!(e.to_string(), "failed to access 'no/such/config.json'");
+        assert!(e.source().is_some());
+    }
+
+    #[test]
+    fn config_error_1() {
+        let path = "/items/17/value".to_string();
+        let e = Error::from(config::Error::InvalidValueType(path, "Number"));
+        assert_eq!(e.to_string(), "invalid config");
+        assert_eq!(e.source().unwrap().to_string(),
+                   "'/items/17/value' must be a Number");
+    }
+}
@@ -24,7 +24,7 @@ impl<'a> FontMetrics<'a> {
         Ok(FontMetrics {
             family: family.to_string(),
             font: f,
-            size: size,
+            size,
             height: (vm.ascent + vm.descent.abs()).round() as u32,
         })
     }
@@ -42,14 +42,11 @@ impl<'a> FontMetrics<'a> {
         let mut min_y = 0;
         let mut last_w = 0;
         for pg in self.font.layout(text, scale, start) {
-            match pg.pixel_bounding_box() {
-                Some(bbox) => {
-                    last_w = bbox.width();
-                    min_y = cmp::min(min_y, -bbox.max.y);
-                    h = cmp::max(h, bbox.max.y + bbox.height());
-                    w = bbox.min.x;
-                }
-                None => {}
+            if let Some(bbox) = pg.pixel_bounding_box() {
+                last_w = bbox.width();
+                min_y = cmp::min(min_y, -bbox.max.y);
+                h = cmp::max(h, bbox.max.y + bbox.height());
+                w = bbox.min.x;
             }
         }
 
@@ -103,7 +100,7 @@ impl FontData {
         Ok(FontData {
             size: find_font_size(&family)?,
             path: find_font_file(&family)?,
-            family: family,
+            family,
         })
     }
 
@@ -136,7 +133,7 @@ fn is_font_exist(family: &str) -> BarhResult<bool> {
             let s = String::from_utf8_lossy(&o.stdout).into_owned();
             Ok(s.lines().any(|x| x == family))
         }
-        Err(e) => Err(Error::from(e)),
+        Err(e) => Err(Error::IoError("fc-list".to_string(), e)),
     }
 }
 
@@ -145,9 +142,9 @@ fn run_fc_match(args: &[&str]) -> BarhResult<String> {
     match res {
         Ok(o) => {
             let s = String::from_utf8_lossy(&o.stdout);
-            return Ok(s.into_owned());
+            Ok(s.into_owned())
         }
-        Err(e) => return Err(Error::from(e)),
+        Err(e) => Err(Error::IoError("fc-match".to_string(), e)),
     }
 }
 
@@ -0,0 +1,146 @@
+use std::io::{self, Read};
+use std::path::Path;
+use std::str;
+
+use serde_json;
+use serde_json::Value;
+use serde_yaml;
+use toml;
+
+use super::{BarhResult, Error, load_file};
+use data;
+
+/// A config file format.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    /// Parses a format name, like `yaml`.
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            _ => None,
+        }
+    }
+
+    /// Detects a format by the file extension.
+    ///
+    /// Unknown extensions are treated as JSON.
+    pub fn from_path(path: &str) -> Format {
+        Path::new(path).extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| Format::from_name(&ext.to_lowercase()))
+            .unwrap_or(Format::Json)
+    }
+}
+
+/// Parses a config file into a JSON value, which can be passed to `Config::from_value`.
+///
+/// CSV and TSV files are converted into a config with `items` only.
+pub fn parse(data: &[u8], format: Format) -> BarhResult<Value> {
+    let value = match format {
+        Format::Json => serde_json::from_slice(data)?,
+        Format::Yaml => serde_yaml::from_slice(data)?,
+        Format::Toml => toml::from_str(str::from_utf8(data)?)?,
+        Format::Csv | Format::Tsv => {
+            let delimiter = if format == Format::Tsv { b'\t' } else { b',' };
+            let items = data::parse_csv(data, &data::CsvOptions::new(delimiter))?;
+
+            let mut conf = serde_json::Map::new();
+            conf.insert("items".to_string(), Value::Array(items));
+            Value::Object(conf)
+        }
+    };
+
+    Ok(value)
+}
+
+/// Reads a file or stdin when `path` is `-`.
+pub fn read(path: &str) -> BarhResult<Vec<u8>> {
+    if path == "-" {
+        let mut v = Vec::new();
+        io::stdin().read_to_end(&mut v).map_err(|e| Error::IoError("stdin".to_string(), e))?;
+        Ok(v)
+    } else {
+        load_file(path)
+    }
+}
+
+/// Returns a directory against which files referenced by the config are resolved.
+///
+/// It's the current directory for stdin.
+pub fn base_dir(path: &str) -> &Path {
+    Path::new(path).parent().unwrap_or_else(|| Path::new(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static JSON: &[u8] = b"{
+        \"title\": \"Title\",
+        \"hor_axis\": { \"suffix\": \"ms\", \"round_tick_values\": true },
+        \"items\": [
+            { \"name\": \"Item 1\", \"value\": 20 },
+            { \"name\": \"Item 2\", \"value\": 50.5 }
+        ]
+    }";
+
+    #[test]
+    fn yaml_1() {
+        let data = b"
+# comment
+title: Title
+hor_axis:
+  suffix: ms
+  round_tick_values: true
+items:
+  - name: Item 1
+    value: 20
+  - name: Item 2
+    value: 50.5
+";
+        assert_eq!(parse(data, Format::Yaml).unwrap(), parse(JSON, Format::Json).unwrap());
+    }
+
+    #[test]
+    fn toml_1() {
+        let data = b"
+# comment
+title = 'Title'
+
+[hor_axis]
+suffix = 'ms'
+round_tick_values = true
+
+[[items]]
+name = 'Item 1'
+value = 20
+
+[[items]]
+name = 'Item 2'
+value = 50.5
+";
+        assert_eq!(parse(data, Format::Toml).unwrap(), parse(JSON, Format::Json).unwrap());
+    }
+
+    #[test]
+    fn from_path_1() {
+        assert_eq!(Format::from_path("a/chart.yaml"), Format::Yaml);
+        assert_eq!(Format::from_path("chart.YML"), Format::Yaml);
+        assert_eq!(Format::from_path("chart.toml"), Format::Toml);
+        assert_eq!(Format::from_path("chart.json"), Format::Json);
+        assert_eq!(Format::from_path("chart.tsv"), Format::Tsv);
+        assert_eq!(Format::from_path("chart"), Format::Json);
+    }
+}
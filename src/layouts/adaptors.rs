@@ -11,7 +11,13 @@ pub trait Adaptor {
     fn append_rect(&mut self, x: u32, y: u32, w: u32, h: u32) -> Node;
     fn append_text(&mut self, text: &str, x: u32, y: u32, fm: &FontMetrics) -> Node;
     fn append_hline(&mut self, x: u32, y: u32, w: u32) -> Node;
-    fn append_vline(&mut self, x: u32, y: u32, h: u32) -> Node;
+    fn append_polygon(&mut self, points: &[(u32, u32)]) -> Node;
+    fn append_polyline(&mut self, points: &[(u32, u32)]) -> Node;
+    fn append_group(&mut self) -> Node;
+    /// Appends a `title` element. Shown as a tooltip and read by screen readers.
+    fn append_title(&mut self, text: &str) -> Node;
+    /// Appends a `desc` element. Not rendered.
+    fn append_desc(&mut self, text: &str) -> Node;
 }
 
 impl Adaptor for Node {
@@ -42,17 +48,6 @@ impl Adaptor for Node {
         text_elem.clone()
     }
 
-    fn append_vline(&mut self, x: u32, y: u32, h: u32) -> Node {
-        let mut rect = self.document().create_element(EId::Rect);
-        self.append(&rect);
-
-        rect.set_attribute((AId::X, x as f64));
-        rect.set_attribute((AId::Y, y as f64));
-        rect.set_attribute((AId::Width, 1));
-        rect.set_attribute((AId::Height, h as f64));
-        rect.clone()
-    }
-
     fn append_hline(&mut self, x: u32, y: u32, w: u32) -> Node {
         let mut rect = self.document().create_element(EId::Rect);
         self.append(&rect);
@@ -63,6 +58,51 @@ impl Adaptor for Node {
         rect.set_attribute((AId::Height, 1));
         rect.clone()
     }
+
+    fn append_polygon(&mut self, points: &[(u32, u32)]) -> Node {
+        let mut polygon = self.document().create_element(EId::Polygon);
+        self.append(&polygon);
+
+        polygon.set_attribute((AId::Points, points_to_string(points)));
+        polygon.clone()
+    }
+
+    fn append_polyline(&mut self, points: &[(u32, u32)]) -> Node {
+        let mut polyline = self.document().create_element(EId::Polyline);
+        self.append(&polyline);
+
+        polyline.set_attribute((AId::Points, points_to_string(points)));
+        polyline.set_attribute((AId::Fill, "none"));
+        polyline.clone()
+    }
+
+    fn append_group(&mut self) -> Node {
+        let g = self.document().create_element(EId::G);
+        self.append(&g);
+        g.clone()
+    }
+
+    fn append_title(&mut self, text: &str) -> Node {
+        append_text_element(self, EId::Title, text)
+    }
+
+    fn append_desc(&mut self, text: &str) -> Node {
+        append_text_element(self, EId::Desc, text)
+    }
+}
+
+fn append_text_element(parent: &mut Node, id: EId, text: &str) -> Node {
+    let mut doc = parent.document();
+    let mut elem = doc.create_element(id);
+    parent.append(&elem);
+
+    let text_node = doc.create_node(NodeType::Text, text);
+    elem.append(&text_node);
+    elem.clone()
+}
+
+fn points_to_string(points: &[(u32, u32)]) -> String {
+    points.iter().map(|&(x, y)| format!("{},{}", x, y)).collect::<Vec<_>>().join(" ")
 }
 
 // fn append_script(parent: &Node) {
@@ -4,23 +4,66 @@ use svgdom::{AttributeId as AId, Node};
 
 use super::layout::{Layout, CalcLayout, DrawLayout};
 use super::adaptors::Adaptor;
-use super::Rect;
+use super::{Rect, Size, Margins};
 use font::FontMetrics;
-use config;
+use color::{self, Color};
+use config::{self, Orientation, Placement, Scale, ValueType};
+use number_format::{NumberFormat, Precision};
+use time;
 
-static FIRST_TICK_COLOR: &'static str = "#333";
-static OTHER_TICKS_COLOR: &'static str = "#ccc";
-static TICKS_TEXT_COLOR: &'static str = "#000";
-static TICKS_COUNT: u32 = 5;
+static DEFAULT_TICKS_COUNT: u32 = 5;
+static MAX_TICKS_COUNT: u32 = 1000;
 static ANNOTATION_BORDER_FACTOR: f32 = 0.2;
-static ANNOTATION_TEXT_COLOR: &'static str = "#fff";
-static ANNOTATION_TEXT_COLOR_ALT: &'static str = "#000";
-static ANNOTATION_HANDLE_COLOR: &'static str = "#999";
+static LABEL_SPACING: i32 = 6;
+static TICK_LABEL_SPACING: u32 = 4;
+/// The largest value is an outlier when it's bigger than the next one by this factor.
+static OUTLIER_FACTOR: f64 = 2.0;
+/// Layout passes made to fit annotations by shrinking the scale.
+static MAX_FIT_ITERATIONS: u32 = 4;
+/// Steps between duration and date ticks in seconds.
+static TIME_STEPS: &[f64] = &[
+    0.001, 0.002, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5,
+    1.0, 2.0, 5.0, 10.0, 15.0, 30.0,
+    60.0, 120.0, 300.0, 600.0, 900.0, 1800.0,
+    3600.0, 7200.0, 10800.0, 21600.0, 43200.0,
+    86400.0, 172800.0, 604800.0,
+];
+static WEEK: f64 = 604800.0;
+
+/// A reason why a bar is shorter than its value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Clip {
+    /// The value is bigger than an explicit axis maximum.
+    Overflow,
+    /// The value is an outlier and the axis is broken.
+    Break,
+}
+
+/// Where an annotation is drawn.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum AnnotationPos {
+    Hidden,
+    Inside,
+    /// Placed inside, but longer than the bar.
+    Overflow,
+    /// After the bar with a handle. Above the column without one.
+    Outside,
+}
 
 pub struct Bar {
     r: Rect,
+    clip: Option<Clip>,
+    /// Error whisker start and end.
+    whisker: Option<(u32, u32)>,
+    target: Option<u32>,
     annotation: String,
-    pub annotation_bbox: Rect,
+    annotation_pos: AnnotationPos,
+    /// An annotation rect in the plot coordinates.
+    annotation_rect: Rect,
+    /// A text position in the layout coordinates. `y` is a baseline.
+    ///
+    /// Also used to align item names when the annotation is hidden.
+    annotation_bbox: Rect,
 }
 
 pub struct Tick {
@@ -30,93 +73,661 @@ pub struct Tick {
     bbox: Rect,
 }
 
+pub struct Label {
+    text: String,
+    bbox: Rect,
+    /// Labels row above the plot area. Zero is the closest one.
+    row: u32,
+}
+
+/// A reference line or a band.
+pub struct Reference {
+    x: u32,
+    /// Zero for lines.
+    w: u32,
+    color: Color,
+    label: Option<Label>,
+}
+
+/// Bars or columns with a value axis.
+///
+/// Everything is calculated in the plot coordinates, where `x` goes along the value axis
+/// and `y` goes across bars, and is converted to the layout ones only for drawing.
+/// For horizontal bars they are the same.
 pub struct BarsLayout<'a> {
     pub lay: Layout,
+    /// The layout size and margins in the plot coordinates.
+    plot: Layout,
     config: &'a config::Config<'a>,
+    scale: Scale,
+    min_value: f64,
     max_value: f64,
     item_height: u32,
     pub bars: Vec<Bar>,
     ticks: Vec<Tick>,
+    minor_ticks: Vec<u32>,
+    references: Vec<Reference>,
+    /// Baselines of the group headers. Centers of the groups for columns.
+    pub group_headers: Vec<u32>,
+    separators: Vec<u32>,
 }
 
 impl<'a> BarsLayout<'a> {
     pub fn new(config: &'a config::Config<'a>) -> BarsLayout<'a> {
         BarsLayout {
             lay: Layout::default(),
-            config: config,
+            plot: Layout::default(),
+            config,
+            scale: Scale::Linear,
+            min_value: 0.0,
             max_value: 0.0,
             item_height: 0,
             bars: Vec::new(),
             ticks: Vec::new(),
+            minor_ticks: Vec::new(),
+            references: Vec::new(),
+            group_headers: Vec::new(),
+            separators: Vec::new(),
         }
     }
-}
 
-impl<'a> CalcLayout for BarsLayout<'a> {
-    fn calc_layout(&mut self, fm: &FontMetrics) {
-        let values = self.config.items.iter().map(|x| x.value).collect::<Vec<f64>>();
-        let max = values.iter().cloned().fold(0./0., f64::max);
-
-        self.max_value = match &self.config.hor_axis {
-            &Some(ref ha) => {
-                match ha.max_value {
-                    Some(mv) => mv,
-                    None => calc_max_value(max),
-                }
+    /// Returns an offset of the value from the start of the axis.
+    fn value_offset(&self, value: f64, width: u32) -> u32 {
+        let k = match self.scale {
+            Scale::Linear => (value - self.min_value) / (self.max_value - self.min_value),
+            Scale::Log(base) => {
+                let min = self.min_value.log(base);
+                (value.log(base) - min) / (self.max_value.log(base) - min)
             }
-            &None => calc_max_value(max),
         };
 
-        self.item_height = (fm.height() as f32 * (1.0 + ANNOTATION_BORDER_FACTOR * 2.0)) as u32;
+        (k.max(0.0) * width as f64) as u32
+    }
 
-        self.lay.size.h =   (self.config.items.len() as u32) * self.item_height
-                          + (self.config.items.len() as u32 + 1) * (self.item_height / 2);
+    /// Calculates reference lines and bands in the plot coordinates
+    /// and arranges their labels into rows, so they will not overlap each other.
+    fn calc_references(&mut self, fm: &FontMetrics, plot_x: u32, plot_w: u32) {
+        let mut list = Vec::new();
 
-        // get hor axis suffix
-        let suffix = match &self.config.hor_axis {
-            &Some(ref axis) => {
-                match axis.suffix {
-                    Some(s) => s,
-                    None => "",
+        for line in &self.config.reference_lines {
+            if line.value < self.min_value || line.value > self.max_value {
+                continue;
+            }
+
+            let x = plot_x + self.value_offset(line.value, plot_w);
+            list.push((x, 0, line.color, line.label));
+        }
+
+        for band in &self.config.bands {
+            if band.to < self.min_value || band.from > self.max_value {
+                continue;
+            }
+
+            let x0 = plot_x + self.value_offset(band.from.max(self.min_value), plot_w);
+            let x1 = plot_x + self.value_offset(band.to.min(self.max_value), plot_w);
+            list.push((x0, x1 - x0, band.color, band.label));
+        }
+
+        list.sort_by_key(|r| r.0 + r.1 / 2);
+
+        // right edges of the labels in each row
+        let mut rows: Vec<i32> = Vec::new();
+        for (x, w, color, label) in list {
+            let label = label.map(|text| {
+                let mut bbox = fm.text_bbox(text);
+                let max_x = self.plot.size.w.saturating_sub(bbox.w) as i32;
+                bbox.x = ((x + w / 2) as i32 - bbox.w as i32 / 2).max(0).min(max_x);
+
+                let row = match rows.iter().position(|end| bbox.x >= end + LABEL_SPACING) {
+                    Some(row) => row,
+                    None => {
+                        rows.push(0);
+                        rows.len() - 1
+                    }
+                };
+                rows[row] = bbox.right() as i32;
+
+                Label { text: text.to_string(), bbox, row: row as u32 }
+            });
+
+            self.references.push(Reference { x, w, color, label });
+        }
+    }
+
+    /// Returns a tick label without a suffix.
+    ///
+    /// The step is not set on a logarithmic scale.
+    fn tick_label(&self, value: f64, step: Option<f64>) -> String {
+        let nf = &self.config.number_format;
+        match (self.config.value_type, step) {
+            (ValueType::Number, _) => {
+                match self.config.hor_axis {
+                    Some(ref ha) if ha.round_tick_values => nf.format(value.round()),
+                    _ => nf.format(value),
                 }
             }
-            &None => "",
+            (ValueType::Duration, Some(step)) => time::format_duration_tick(value, step, nf),
+            (ValueType::Duration, None) => time::format_duration(value, nf),
+            (ValueType::Date, Some(step)) => time::format_date_tick(value, step),
+            (ValueType::Date, None) => time::format_timestamp(value),
+        }
+    }
+
+    fn annotation(&self, value: f64) -> String {
+        let nf = &self.config.number_format;
+        match self.config.value_type {
+            ValueType::Number => nf.format(value),
+            ValueType::Duration => time::format_duration(value, nf),
+            ValueType::Date => time::format_timestamp(value),
+        }
+    }
+
+    /// Returns an annotation text of the item.
+    ///
+    /// `total` is a sum of all bar lengths and is used by the `{percent}` placeholder.
+    fn item_annotation(&self, item: &config::Item, total: f64) -> String {
+        let value = self.item_value(item);
+
+        let template = match item.annotation.template {
+            Some(t) => t,
+            None => return value,
+        };
+
+        let suffix = self.suffix();
+
+        let nf = NumberFormat {
+            precision: Precision::Significant(3),
+            decimal_mark: self.config.number_format.decimal_mark,
+            ..NumberFormat::default()
+        };
+        let percent = if total > 0.0 { item.length() / total * 100.0 } else { 0.0 };
+
+        template
+            .replace("{name}", item.name)
+            .replace("{value}", &value)
+            .replace("{suffix}", suffix)
+            .replace("{percent}", &nf.format(percent))
+    }
+
+    /// Returns a default annotation of the item.
+    fn item_value(&self, item: &config::Item) -> String {
+        match item.start {
+            Some(start) => self.range_annotation(start, item.value),
+            None => self.annotation(item.value),
+        }
+    }
+
+    /// Returns the horizontal axis suffix.
+    fn suffix(&self) -> &'a str {
+        match self.config.hor_axis {
+            Some(ref axis) => axis.suffix.unwrap_or(""),
+            None => "",
+        }
+    }
+
+    /// Returns a chart description for screen readers.
+    pub fn summary(&self) -> String {
+        let items = &self.config.items;
+        let kind = if self.is_vertical() { "Column chart" } else { "Bar chart" };
+        let mut text = format!("{} with {}", kind, plural(items.len(), "item"));
+        if !self.config.groups.is_empty() {
+            text += &format!(" in {}", plural(self.config.groups.len(), "group"));
+        }
+        text.push('.');
+
+        // range bars are compared by their length, like in sorting
+        let cmp_values = |a: &&config::Item, b: &&config::Item| {
+            a.length().partial_cmp(&b.length()).unwrap_or(cmp::Ordering::Equal)
         };
+        let min = items.iter().min_by(cmp_values);
+        let max = items.iter().max_by(cmp_values);
+        if let (Some(min), Some(max)) = (min, max) {
+            let suffix = self.suffix();
+            if items.len() == 1 {
+                text += &format!(" {}: {}{}.", max.name, self.item_value(max), suffix);
+            } else {
+                text += &format!(" Values range from {}{} ({}) to {}{} ({}).",
+                                 self.item_value(min), suffix, min.name,
+                                 self.item_value(max), suffix, max.name);
+            }
+        }
+
+        text
+    }
+
+    /// Returns all items as CSV. Optional columns are added only when used.
+    pub fn data_table(&self) -> String {
+        let items = &self.config.items;
+        let has_groups = !self.config.groups.is_empty();
+        let has_errors = items.iter().any(|item| item.error.is_some());
+        let has_targets = items.iter().any(|item| item.target.is_some());
+
+        let mut header = vec!["Name", "Value"];
+        if has_groups {
+            header.insert(0, "Group");
+        }
+        if has_errors {
+            header.push("Error");
+        }
+        if has_targets {
+            header.push("Target");
+        }
+
+        let suffix = self.suffix();
+        let mut lines = vec![header.join(",")];
+        for (idx, item) in items.iter().enumerate() {
+            let mut row = Vec::new();
+            if has_groups {
+                let group = self.config.groups.iter()
+                    .find(|g| idx >= g.start && idx < g.start + g.len);
+                row.push(group.map(|g| g.name).unwrap_or("").to_string());
+            }
 
-        fn gen_ticks_list(max_value: f64, count: u32) -> Vec<f64> {
-            let mut v = Vec::new();
-            let num_step = max_value / (count - 1) as f64;
-            let mut n = 0.0;
-            for _ in 0..count {
-                v.push(n);
-                n += num_step;
+            row.push(item.name.to_string());
+            row.push(self.item_value(item) + suffix);
+
+            if has_errors {
+                row.push(match item.error {
+                    Some((low, high)) if low == high => format!("±{}{}", self.delta(low), suffix),
+                    Some((low, high)) => {
+                        format!("-{}{} +{}{}", self.delta(low), suffix, self.delta(high), suffix)
+                    }
+                    None => String::new(),
+                });
             }
 
-            v
+            if has_targets {
+                row.push(item.target.map(|v| self.annotation(v) + suffix).unwrap_or_default());
+            }
+
+            let row: Vec<String> = row.iter().map(|s| csv_field(s)).collect();
+            lines.push(row.join(","));
         }
 
-        // calc ticks text
-        let ticks_value = match &self.config.hor_axis {
-            &Some(ref axis) => {
-                match axis.ticks {
-                    Some(ref l) => l.clone(),
-                    None => gen_ticks_list(self.max_value, TICKS_COUNT),
+        lines.join("\n")
+    }
+
+    /// Returns a difference between values. Durations for time values.
+    fn delta(&self, value: f64) -> String {
+        let nf = &self.config.number_format;
+        match self.config.value_type {
+            ValueType::Number => nf.format(value),
+            ValueType::Duration | ValueType::Date => time::format_duration(value, nf),
+        }
+    }
+
+    /// Returns a range bar annotation.
+    ///
+    /// Durations and dates are shown as a duration of the range.
+    fn range_annotation(&self, start: f64, end: f64) -> String {
+        let nf = &self.config.number_format;
+        match self.config.value_type {
+            ValueType::Number => format!("{}–{}", nf.format(start), nf.format(end)),
+            ValueType::Duration | ValueType::Date => time::format_duration(end - start, nf),
+        }
+    }
+
+    fn is_vertical(&self) -> bool {
+        self.config.orientation == Orientation::Vertical
+    }
+
+    fn plot_width(&self) -> u32 {
+        self.plot.size.w.saturating_sub(self.plot.margins.left + self.plot.margins.right)
+    }
+
+    /// Returns positions of item names. Baselines for bars and centers for columns.
+    pub fn item_ticks(&self) -> Vec<u32> {
+        self.bars.iter()
+            .map(|bar| {
+                if self.is_vertical() {
+                    bar.r.y as u32 + self.item_height / 2
+                } else {
+                    bar.annotation_bbox.y as u32
                 }
+            })
+            .collect()
+    }
+
+    /// Converts a rect from the plot coordinates to the layout ones.
+    fn map_rect(&self, r: &Rect) -> Rect {
+        match self.config.orientation {
+            Orientation::Horizontal => Rect::new(r.x, r.y, r.w, r.h),
+            // the value axis goes up
+            Orientation::Vertical => {
+                Rect::new(r.y, self.plot.size.w as i32 - r.x - r.w as i32, r.h, r.w)
+            }
+        }
+    }
+
+    /// Converts points from the plot coordinates to the layout ones and moves them.
+    fn map_points(&self, x: u32, y: u32, points: &[(u32, u32)]) -> Vec<(u32, u32)> {
+        points.iter()
+            .map(|&(px, py)| match self.config.orientation {
+                Orientation::Horizontal => (x + px, y + py),
+                Orientation::Vertical => (x + py, y + self.plot.size.w - px),
+            })
+            .collect()
+    }
+
+    /// Appends a rect in the plot coordinates.
+    fn append_plot_rect(&self, root: &mut Node, x: u32, y: u32, r: Rect) -> Node {
+        let r = self.map_rect(&r);
+        root.append_rect((x as i32 + r.x) as u32, (y as i32 + r.y) as u32, r.w, r.h)
+    }
+
+    /// Calculates positions of ticks, references and bars using current margins.
+    fn calc_positions(&mut self, fm: &FontMetrics, bars_h: u32, break_value: Option<f64>) {
+        let max_text_h = fm.full_height();
+
+        let plot_w = self.plot_width();
+        let plot_x = self.plot.margins.left;
+        self.references.clear();
+        self.bars.clear();
+        self.separators.clear();
+        self.group_headers.clear();
+
+        self.calc_references(fm, plot_x, plot_w);
+        let label_rows = self.references.iter()
+            .filter_map(|r| r.label.as_ref().map(|l| l.row + 1))
+            .max()
+            .unwrap_or(0);
+
+        // column labels are placed on the right instead
+        if !self.is_vertical() {
+            self.plot.margins.top = max_text_h / 2 + label_rows * max_text_h;
+        }
+        self.plot.size.h = bars_h + self.plot.margins.top + self.plot.margins.bottom;
+
+        let r2 = self.plot.size.to_rect(0, 0).adjusted(&self.plot.margins);
+
+        // calc ticks pos
+        let positions: Vec<u32> = self.ticks.iter()
+            .map(|tick| r2.x as u32 + self.value_offset(tick.value, r2.w))
+            .collect();
+        for (tick, pos) in self.ticks.iter_mut().zip(positions) {
+            tick.pos = pos;
+        }
+
+        // labels are placed above the plot area
+        for label in self.references.iter_mut().filter_map(|r| r.label.as_mut()) {
+            let top = r2.y - ((label.row + 1) * max_text_h) as i32;
+            label.bbox.y = top + fm.height() as i32;
+        }
+
+        if let Scale::Log(base) = self.scale {
+            self.minor_ticks = gen_log_minor_ticks_list(self.min_value, self.max_value, base)
+                .into_iter()
+                .map(|n| r2.x as u32 + self.value_offset(n, r2.w))
+                .collect();
+        }
+
+        let total: f64 = self.config.items.iter().map(config::Item::length).sum();
+
+        // calc bars
+        let mut y = (self.item_height as i32 / 2) + r2.y;
+        for (idx, item) in self.config.items.iter().enumerate() {
+            if let Some(group) = self.config.groups.iter().find(|g| g.start == idx) {
+                if idx != 0 {
+                    self.separators.push(y as u32);
+                    y += group_gap(self.item_height) as i32;
+                }
+
+                // column group headers are drawn below item names
+                if !self.is_vertical() {
+                    let ann_border =
+                        (self.item_height as f32 * ANNOTATION_BORDER_FACTOR) as u32;
+                    let text_h = fm.text_bbox(group.name).h;
+                    self.group_headers.push(y as u32 + text_h + ann_border);
+                    y += (self.item_height as f32 * 1.5) as i32;
+                }
+            }
+
+            let x0 = match item.start {
+                Some(start) => cmp::min(self.value_offset(start, r2.w), r2.w),
+                None => 0,
+            };
+
+            let (x1, clip) = if item.value > self.max_value {
+                let is_outlier = Some(upper_value(item)) == break_value;
+                let clip = if is_outlier { Clip::Break } else { Clip::Overflow };
+                (r2.w, Some(clip))
+            } else {
+                (self.value_offset(item.value, r2.w), None)
+            };
+            let w = x1 - x0;
+            let bx = r2.x as u32 + x0;
+
+            let whisker = item.error.map(|(low, high)| {
+                let offset = |v: f64| cmp::min(self.value_offset(v, r2.w), r2.w);
+                (r2.x as u32 + offset(item.value - low), r2.x as u32 + offset(item.value + high))
+            });
+            let target = item.target
+                .map(|v| r2.x as u32 + cmp::min(self.value_offset(v, r2.w), r2.w));
+
+            // annotations should not overlap whiskers
+            let (inner_w, outer_x) = match whisker {
+                Some((low, high)) => (low.saturating_sub(bx), cmp::max(high, bx + w)),
+                None => (w, bx + w),
+            };
+
+            let annotation = self.item_annotation(item, total);
+            // the bbox of a hidden annotation is still used for alignment
+            let text_bbox = if annotation.is_empty() {
+                fm.text_bbox(&self.annotation(item.value))
+            } else {
+                fm.text_bbox(&annotation)
+            };
+            let ann_border = (self.item_height as f32 * ANNOTATION_BORDER_FACTOR) as u32;
+            let ann_handle_w = (fm.height() as f32 * 0.75) as u32;
+
+            // a text is not rotated, so it's along the value axis only in bars
+            let (text_len, text_thickness) = if self.is_vertical() {
+                (text_bbox.h, text_bbox.w)
+            } else {
+                (text_bbox.w, text_bbox.h)
+            };
+
+            let fits = text_len + ann_border * 2 < inner_w
+                && (!self.is_vertical() || text_thickness <= self.item_height);
+            let placement = item.annotation.placement;
+            let pos = if !item.annotation.show || annotation.is_empty() {
+                AnnotationPos::Hidden
+            } else {
+                match (placement, fits) {
+                    (Placement::Outside, _) | (Placement::Auto, false) => AnnotationPos::Outside,
+                    (_, true) => AnnotationPos::Inside,
+                    (_, false) => AnnotationPos::Overflow,
+                }
+            };
+
+            let tx = match (pos, placement) {
+                // a hidden text can be longer than the bar
+                (AnnotationPos::Hidden, _) => bx,
+                (AnnotationPos::Outside, _) => {
+                    let mut x = outer_x;
+                    if clip == Some(Clip::Overflow) {
+                        x += overflow_marker_width(self.item_height);
+                    }
+
+                    if self.is_vertical() { x + ann_border } else { x + ann_handle_w + 2 }
+                }
+                // a long text starts at the bar start
+                (AnnotationPos::Overflow, _) | (_, Placement::InsideStart) => bx + ann_border,
+                (_, Placement::Center) => bx + (inner_w - text_len) / 2,
+                _ => bx + inner_w - text_len - ann_border,
+            };
+            let ty = if self.is_vertical() {
+                y + (self.item_height as i32 - text_thickness as i32) / 2
+            } else {
+                y + ann_border as i32
+            };
+
+            self.bars.push(Bar {
+                r: Rect::new(bx as i32, y, w, self.item_height),
+                clip,
+                whisker,
+                target,
+                annotation,
+                annotation_pos: pos,
+                annotation_rect: Rect::new(tx as i32, ty, text_len, text_thickness),
+                // will be calculated later
+                annotation_bbox: Rect::default(),
+            });
+
+            y += (self.item_height as f32 * 1.5) as i32;
+        }
+
+        if self.is_vertical() {
+            for group in &self.config.groups {
+                let first = &self.bars[group.start];
+                let last = &self.bars[group.start + group.len - 1];
+                let center = (first.r.y + last.r.y) as u32 / 2 + self.item_height / 2;
+                self.group_headers.push(center);
+            }
+        }
+    }
+
+    /// Returns how far annotations stick out of the layout along the value axis.
+    fn annotations_overflow(&self) -> u32 {
+        let right = self.bars.iter()
+            .filter(|bar| bar.annotation_pos != AnnotationPos::Hidden)
+            .map(|bar| bar.annotation_rect.right())
+            .max()
+            .unwrap_or(0);
+
+        right.saturating_sub(self.plot.size.w)
+    }
+
+    /// Converts text positions to the layout coordinates and sets the layout size.
+    fn map_to_layout(&mut self) {
+        let boxes: Vec<Rect> = self.bars.iter().map(|bar| self.map_rect(&bar.annotation_rect))
+            .collect();
+        for (bar, r) in self.bars.iter_mut().zip(boxes) {
+            bar.annotation_bbox = Rect::new(r.x, r.y + r.h as i32, r.w, r.h);
+        }
+
+        let plot = &self.plot;
+        match self.config.orientation {
+            Orientation::Horizontal => {
+                self.lay.size = Size { w: plot.size.w, h: plot.size.h };
+                self.lay.margins = Margins {
+                    left: plot.margins.left,
+                    top: plot.margins.top,
+                    right: plot.margins.right,
+                    bottom: plot.margins.bottom,
+                };
+            }
+            Orientation::Vertical => {
+                // labels are on the right of the plot area and centered on the lines
+                let label_x = (plot.size.h - plot.margins.bottom) as i32 + LABEL_SPACING;
+                for r in &mut self.references {
+                    let cy = plot.size.w - r.x - r.w / 2;
+                    if let Some(ref mut label) = r.label {
+                        label.bbox.x = label_x;
+                        label.bbox.y = (cy + label.bbox.h / 2) as i32;
+                    }
+                }
+
+                self.lay.size = Size { w: plot.size.h, h: plot.size.w };
+                self.lay.margins = Margins {
+                    left: plot.margins.top,
+                    top: plot.margins.right,
+                    right: plot.margins.bottom,
+                    bottom: plot.margins.left,
+                };
+            }
+        }
+    }
+}
+
+impl<'a> CalcLayout for BarsLayout<'a> {
+    fn calc_layout(&mut self, fm: &FontMetrics) {
+        // upper whiskers must fit into the axis
+        let values = self.config.items.iter().map(upper_value).collect::<Vec<f64>>();
+        let mut max = values.iter().cloned().fold(f64::NAN, f64::max);
+
+        // reference lines, bands and targets must be visible too
+        let extra_values: Vec<f64> = self.config.reference_lines.iter().map(|l| l.value)
+            .chain(self.config.bands.iter().flat_map(|b| vec![b.from, b.to]))
+            .chain(self.config.items.iter().filter_map(|x| x.target))
+            .collect();
+
+        let (min_value, max_value, tick_count, tick_step) = match self.config.hor_axis {
+            Some(ref ha) => {
+                self.scale = ha.scale;
+                (ha.min_value, ha.max_value, ha.tick_count, ha.tick_step)
+            }
+            None => (None, None, None, None),
+        };
+
+        // an outlier bar is drawn truncated, so the axis is based on the next value
+        let mut break_value = None;
+        if let Some(config::HorAxis { axis_break: true, .. }) = self.config.hor_axis {
+            if let Some(next) = find_outlier_next(&values) {
+                break_value = Some(max);
+                max = next;
+            }
+        }
+
+        max = extra_values.iter().cloned().fold(max, f64::max);
+
+        // range bars start after the axis origin
+        let min = self.config.items.iter()
+            .map(|x| x.start.unwrap_or(x.value))
+            .chain(extra_values.iter().cloned())
+            .fold(f64::NAN, f64::min);
+        let tick_count = tick_count.unwrap_or(DEFAULT_TICKS_COUNT);
+
+        let (auto_ticks, step) = match (self.scale, self.config.value_type) {
+            (Scale::Linear, ValueType::Number) => {
+                let min_value = min_value.unwrap_or(0.0);
+                let (max_value, tick_step) = calc_axis(min_value, max, max_value, tick_count,
+                                                       tick_step);
+                self.min_value = min_value;
+                self.max_value = max_value;
+                (gen_ticks_list(self.min_value, self.max_value, tick_step), Some(tick_step))
+            }
+            (Scale::Linear, value_type) => {
+                // durations start at zero and dates at the earliest one
+                let min = if value_type == ValueType::Date { min } else { 0.0 };
+                let (min_value, max_value, tick_step) =
+                    calc_time_axis(min, max, min_value, max_value, tick_count, tick_step);
+                self.min_value = min_value;
+                self.max_value = max_value;
+                (gen_ticks_list(self.min_value, self.max_value, tick_step), Some(tick_step))
+            }
+            (Scale::Log(base), _) => {
+                let (min_value, max_value) = calc_log_axis(min, max, min_value, max_value, base);
+                self.min_value = min_value;
+                self.max_value = max_value;
+                (gen_log_ticks_list(min_value, max_value, base), None)
             }
-            &None => gen_ticks_list(self.max_value, TICKS_COUNT),
+        };
+
+        self.item_height = (fm.height() as f32 * (1.0 + ANNOTATION_BORDER_FACTOR * 2.0)) as u32;
+
+        // group headers take a row and are separated by an extra spacing
+        let headers = if self.is_vertical() { 0 } else { self.config.groups.len() };
+        let rows = (self.config.items.len() + headers) as u32;
+        let gaps = self.config.groups.iter().filter(|g| g.start != 0).count() as u32;
+        self.plot.size.h =   rows * self.item_height
+                          + (rows + 1) * (self.item_height / 2)
+                          + gaps * group_gap(self.item_height);
+
+        // get hor axis suffix
+        let suffix = self.suffix();
+
+        // calc ticks text
+        let ticks_value = match self.config.hor_axis {
+            Some(config::HorAxis { ticks: Some(ref l), .. }) => {
+                l.iter().cloned().filter(|n| *n >= self.min_value).collect()
+            }
+            _ => auto_ticks,
         };
 
         {
             for n in ticks_value {
-                let text1 = if let Some(ref ha) = self.config.hor_axis {
-                    if ha.round_tick_values {
-                        n.round().to_string() + suffix
-                    } else {
-                        n.to_string() + suffix
-                    }
-                } else {
-                    n.to_string() + suffix
-                };
+                let text1 = self.tick_label(n, step) + suffix;
                 let bbox1 = fm.text_bbox(&text1);
 
                 self.ticks.push(Tick {
@@ -128,8 +739,8 @@ impl<'a> CalcLayout for BarsLayout<'a> {
             }
         }
 
-        // first tick is always '0'
-        let min_v_bbox = fm.text_bbox(&("0".to_string() + suffix));
+        // first tick is always the axis minimum
+        let min_v_bbox = fm.text_bbox(&(self.tick_label(self.min_value, step) + suffix));
         let min_text_w = min_v_bbox.w;
 
         // find widest tick text
@@ -140,75 +751,72 @@ impl<'a> CalcLayout for BarsLayout<'a> {
         }
         // and max value
         {
-            let max_v_bbox = fm.text_bbox(&(self.max_value.to_string() + suffix));
+            let max_v_bbox = fm.text_bbox(&(self.tick_label(self.max_value, step) + suffix));
             max_text_w = cmp::max(max_v_bbox.w, max_text_w);
         }
         let max_text_h = fm.full_height();
 
+        // column tick labels are stacked, so their height matters
+        let tick_spacing = if self.is_vertical() { max_text_h } else { max_text_w };
+
         // TODO: move spacing to options
-        self.lay.size.w = match &self.config.hor_axis {
-            &Some(ref axis) => {
+        self.plot.size.w = match self.config.hor_axis {
+            Some(ref axis) => {
                 match axis.width {
                     Some(ref w) => *w,
-                    None => max_text_w * (self.ticks.len() as u32 * 3),
+                    None => tick_spacing * (self.ticks.len() as u32 * 3),
                 }
             }
-            &None => max_text_w * (self.ticks.len() as u32 * 3),
+            None => tick_spacing * (self.ticks.len() as u32 * 3),
         };
 
-        let text_factor = 2.0;
-        self.lay.margins.left = (min_text_w as f32 / text_factor) as u32;
-        self.lay.margins.right = (max_text_w as f32 / text_factor) as u32;
-        self.lay.margins.top = max_text_h / 2;
-        self.lay.margins.bottom = max_text_h as u32;
-
-        self.lay.size.h += self.lay.margins.top + self.lay.margins.bottom;
+        if self.is_vertical() {
+            // tick labels are on the left and centered on the tick lines
+            self.plot.margins.left = max_text_h / 2;
+            self.plot.margins.right = max_text_h / 2;
+            self.plot.margins.top = max_text_w + TICK_LABEL_SPACING;
 
-        let r2 = self.lay.size.into_rect(0, 0).adjusted(&self.lay.margins);
-        let scale_factor = r2.w as f64 / self.max_value as f64;
+            let max_label_w = self.config.reference_lines.iter().map(|l| l.label)
+                .chain(self.config.bands.iter().map(|b| b.label))
+                .flatten()
+                .map(|text| fm.text_bbox(text).w + LABEL_SPACING as u32)
+                .max();
+            self.plot.margins.bottom = max_label_w.unwrap_or(0);
+        } else {
+            let text_factor = 2.0;
+            self.plot.margins.left = (min_text_w as f32 / text_factor) as u32;
+            self.plot.margins.right = (max_text_w as f32 / text_factor) as u32;
 
-        // calc ticks pos
-        {
-            for tick in self.ticks.iter_mut() {
-                let x = (tick.value * scale_factor) as u32;
-                tick.pos = r2.x as u32 + x;
-            }
+            self.plot.margins.bottom = max_text_h;
         }
 
-        // calc bars
-        let mut y = (self.item_height as i32 / 2) + r2.y;
-        for item in &self.config.items {
-            let w = (item.value * scale_factor) as u32;
-
-            let annotation = format!("{}", item.value);
-            let text_bbox = fm.text_bbox(&annotation);
-            let ann_border = (self.item_height as f32 * ANNOTATION_BORDER_FACTOR) as u32;
-            let ann_handle_w = (fm.height() as f32 * 0.75) as u32;
-
-            let ann_bbox;
-            if text_bbox.w + ann_border * 2 < w {
-                // can be written inside the bar
-                let tx = r2.x as u32 + w - text_bbox.w - ann_border;
-                let ty = y as u32 + text_bbox.h + ann_border;
-
-                ann_bbox = Rect::new(tx as i32, ty as i32, text_bbox.w, text_bbox.h);
-            } else {
-                // should be written outside
-                let x = r2.x as u32 + w;
-                let tx = x + ann_handle_w + 2;
-                let ty = y as u32 + text_bbox.h + ann_border;
-
-                ann_bbox = Rect::new(tx as i32, ty as i32, text_bbox.w, text_bbox.h);
+        // annotations after bars must fit into the layout
+        let bars_h = self.plot.size.h;
+        let is_fixed_width = self.config.hor_axis.as_ref().is_some_and(|a| a.width.is_some());
+        let min_plot_w = self.plot_width() / 2;
+        self.calc_positions(fm, bars_h, break_value);
+        for _ in 0..MAX_FIT_ITERATIONS {
+            let overflow = self.annotations_overflow();
+            if overflow == 0 {
+                break;
             }
 
-            self.bars.push(Bar {
-                r: Rect::new(r2.x as i32, y as i32, w, self.item_height),
-                annotation: annotation,
-                annotation_bbox: ann_bbox,
-            });
+            // a fixed width is kept by shrinking the scale, but not too much
+            self.plot.margins.right += overflow;
+            if !is_fixed_width || self.plot_width() < min_plot_w {
+                self.plot.size.w += overflow;
+            }
 
-            y += (self.item_height as f32 * 1.5) as i32;
+            self.calc_positions(fm, bars_h, break_value);
         }
+
+        // the scale is kept when the passes were not enough,
+        // so positions are still valid after the layout is widened
+        let overflow = self.annotations_overflow();
+        self.plot.margins.right += overflow;
+        self.plot.size.w += overflow;
+
+        self.map_to_layout();
     }
 }
 
@@ -219,23 +827,69 @@ impl<'a> DrawLayout for BarsLayout<'a> {
             lay_rect.set_attribute((AId::Stroke, "red"));
         }
 
-        let r2 = self.lay.size.into_rect(x as i32, y as i32).adjusted(&self.lay.margins);
+        // in the plot coordinates
+        let r2 = self.plot.size.to_rect(0, 0).adjusted(&self.plot.margins);
         if self.lay.debug {
-            let mut lay_rect = root.append_rect(r2.x as u32, r2.y as u32, r2.w, r2.h);
+            let mut lay_rect = self.append_plot_rect(root, x, y, Rect::new(r2.x, r2.y, r2.w, r2.h));
             lay_rect.set_attribute((AId::Stroke, "green"));
         }
 
-        let tx = fm.height();
+        let theme = &self.config.theme;
+        let pa = &self.config.plot_area;
+
+        // the plot area is the bottom layer
+        if pa.background != "none" || pa.border.is_some() {
+            let mut rect = self.append_plot_rect(root, x, y,
+                                                 Rect::new(r2.x, r2.y, r2.w + 1, r2.h));
+            rect.set_attribute((AId::Fill, pa.background));
+
+            if let Some(color) = pa.border {
+                rect.set_attribute((AId::Stroke, color));
+                rect.set_attribute((AId::StrokeWidth, pa.border_width as f64));
+            }
+
+            if pa.radius != 0 {
+                rect.set_attribute((AId::Rx, pa.radius as f64));
+                rect.set_attribute((AId::Ry, pa.radius as f64));
+            }
+        }
+
+        // bands are drawn above the plot area
+        for r in self.references.iter().filter(|r| r.w != 0) {
+            let band_r = Rect::new(r.x as i32, r2.y, r.w, r2.h);
+            let mut rect = self.append_plot_rect(root, x, y, band_r);
+            rect.set_attribute((AId::Fill, r.color));
+        }
+
+        if self.config.group_separator {
+            for sy in &self.separators {
+                let mut line = self.append_plot_rect(root, x, y,
+                                                     Rect::new(r2.x, *sy as i32, r2.w, 1));
+                line.set_attribute((AId::Fill, theme.group_separator_color));
+            }
+        }
+
+        for pos in &self.minor_ticks {
+            let mut tick_vl = self.append_plot_rect(root, x, y,
+                                                    Rect::new(*pos as i32, r2.y, 1, r2.h));
+            tick_vl.set_attribute((AId::Fill, theme.minor_tick_color));
+        }
 
         for tick in self.ticks.iter() {
             // draw tick line
-            let mut tick_vl = root.append_vline(x + tick.pos, r2.y as u32, r2.h);
-            tick_vl.set_attribute((AId::Fill, OTHER_TICKS_COLOR));
+            let mut tick_vl = self.append_plot_rect(root, x, y,
+                                                    Rect::new(tick.pos as i32, r2.y, 1, r2.h));
+            tick_vl.set_attribute((AId::Fill, theme.tick_color));
 
-            let ty = r2.y as u32 + r2.h + tx - 2;
-            let tx = (x + tick.pos - tick.bbox.w / 2) as u32;
+            // column ticks are on the left of the plot area
+            let (tx, ty) = if self.is_vertical() {
+                let (_, py) = self.map_points(x, y, &[(tick.pos, 0)])[0];
+                (x + r2.y as u32 - TICK_LABEL_SPACING - tick.bbox.w, py + tick.bbox.h / 2)
+            } else {
+                (x + tick.pos - tick.bbox.w / 2, y + r2.y as u32 + r2.h + fm.height() - 2)
+            };
             let mut text_node1 = root.append_text(&tick.title, tx, ty, fm);
-            text_node1.set_attribute((AId::Fill, TICKS_TEXT_COLOR));
+            text_node1.set_attribute((AId::Fill, theme.tick_text_color));
 
             if self.lay.debug {
                 let mut rect = root.append_rect(tx, ty - tick.bbox.h, tick.bbox.w, tick.bbox.h);
@@ -245,34 +899,389 @@ impl<'a> DrawLayout for BarsLayout<'a> {
 
         // draw bars
         for (item, bar) in self.config.items.iter().zip(self.bars.iter()) {
-            let mut rect = root.append_rect(bar.r.x as u32 + x, bar.r.y as u32 + y, bar.r.w, bar.r.h);
+            // a titled group is announced by screen readers as a single element
+            let mut g = root.append_group();
+            g.append_title(&format!("{}: {}{}", item.name, self.item_value(item), self.suffix()));
+            let root = &mut g;
+
+            let mut rect = self.append_plot_rect(root, x, y,
+                                                 Rect::new(bar.r.x, bar.r.y, bar.r.w, bar.r.h));
             rect.set_attribute((AId::Fill, item.color));
 
-            let ann_color;
-            if bar.r.right() > bar.annotation_bbox.right() {
-                ann_color = ANNOTATION_TEXT_COLOR;
-            } else {
-                ann_color = ANNOTATION_TEXT_COLOR_ALT;
+            let bx = bar.r.x as u32;
+            let by = bar.r.y as u32;
+
+            if bar.clip == Some(Clip::Break) {
+                // zig-zag in the middle of the bar
+                let cx = bx + bar.r.w / 2;
+                let dx = bar.r.h / 8;
+                let h = bar.r.h;
+                let mut marker = root.append_polyline(&self.map_points(x, y, &[
+                    (cx - dx, by - 1),
+                    (cx + dx, by + h / 4),
+                    (cx - dx, by + h / 2),
+                    (cx + dx, by + h * 3 / 4),
+                    (cx - dx, by + h + 1),
+                ]));
+                marker.set_attribute((AId::Stroke, theme.break_marker_color));
+                marker.set_attribute((AId::StrokeWidth, theme.break_marker_width as f64));
+            }
 
+            if bar.annotation_pos == AnnotationPos::Outside && !self.is_vertical() {
                 // draw handle
                 let hx = x + bar.r.right();
                 let hy = y + bar.r.y as u32 + self.item_height / 2;
                 let hw = bar.annotation_bbox.x as u32 - bar.r.right() - 2;
                 let mut hline = root.append_hline(hx, hy, hw);
-                hline.set_attribute((AId::Fill, ANNOTATION_HANDLE_COLOR));
+                hline.set_attribute((AId::Fill, theme.annotation_handle_color));
             }
 
-            let mut text_node = root.append_text(&bar.annotation, x + bar.annotation_bbox.x as u32,
-                                             y + bar.annotation_bbox.y as u32, fm);
-            text_node.set_attribute((AId::Fill, ann_color));
+            if bar.annotation_pos != AnnotationPos::Hidden {
+                let mut text_node = root.append_text(&bar.annotation,
+                                                     x + bar.annotation_bbox.x as u32,
+                                                     y + bar.annotation_bbox.y as u32, fm);
+                if bar.annotation_pos == AnnotationPos::Inside {
+                    // theme colors are already checked by config
+                    let preferred = color::parse(theme.annotation_text_color).unwrap();
+                    let c = color::text_color_on(item.color, preferred);
+                    text_node.set_attribute((AId::Fill, c));
+                } else {
+                    text_node.set_attribute((AId::Fill, theme.annotation_text_color_alt));
+                }
+            }
+
+            if let Some((low, high)) = bar.whisker {
+                let cy = by + bar.r.h / 2;
+                let cap_h = bar.r.h / 2;
+
+                // a whisker can't be thicker than its bar
+                let lw = cmp::min(theme.whisker_width, bar.r.h);
+
+                let r = Rect::new(low as i32, (cy - lw / 2) as i32, high - low, lw);
+                let mut line = self.append_plot_rect(root, x, y, r);
+                line.set_attribute((AId::Fill, theme.whisker_color));
+
+                for cx in &[low, high] {
+                    let cx = cx.saturating_sub(lw / 2);
+                    let r = Rect::new(cx as i32, (cy - cap_h / 2) as i32, lw, cap_h);
+                    let mut cap = self.append_plot_rect(root, x, y, r);
+                    cap.set_attribute((AId::Fill, theme.whisker_color));
+                }
+            }
+
+            if let Some(tx) = bar.target {
+                let th = bar.r.h * 7 / 10;
+                let r = Rect::new(tx as i32 - 1, (by + (bar.r.h - th) / 2) as i32, 3, th);
+                let mut marker = self.append_plot_rect(root, x, y, r);
+                marker.set_attribute((AId::Fill, theme.target_color));
+            }
+
+            if bar.clip == Some(Clip::Overflow) {
+                // an arrow after the end of the bar
+                let right = bar.r.right();
+                let mut marker = root.append_polygon(&self.map_points(x, y, &[
+                    (right, by),
+                    (right + overflow_marker_width(bar.r.h), by + bar.r.h / 2),
+                    (right, by + bar.r.h),
+                ]));
+                marker.set_attribute((AId::Fill, item.color));
+            }
+        }
+
+        for r in &self.references {
+            if r.w == 0 {
+                // a wide line is cut by the plot edges
+                let lx = r.x.saturating_sub(theme.reference_line_width / 2);
+                let lw = cmp::min(theme.reference_line_width, self.plot.size.w - lx);
+                let line_r = Rect::new(lx as i32, r2.y, lw, r2.h);
+                let mut line = self.append_plot_rect(root, x, y, line_r);
+                line.set_attribute((AId::Fill, r.color));
+            }
+
+            if let Some(ref label) = r.label {
+                let mut text = root.append_text(&label.text, x + label.bbox.x as u32,
+                                                y + label.bbox.y as u32, fm);
+                if r.w == 0 {
+                    text.set_attribute((AId::Fill, r.color));
+                } else {
+                    text.set_attribute((AId::Fill, theme.band_text_color));
+                }
+            }
         }
 
         // first tick should be drawn last, so it will be above bars
-        let mut first_tick_vl = root.append_vline(r2.x as u32, r2.y as u32, r2.h);
-        first_tick_vl.set_attribute((AId::Fill, FIRST_TICK_COLOR));
+        let mut first_tick_vl = self.append_plot_rect(root, x, y, Rect::new(r2.x, r2.y, 1, r2.h));
+        first_tick_vl.set_attribute((AId::Fill, theme.axis_color));
+    }
+}
+
+/// Returns an extra spacing before a group.
+fn group_gap(item_height: u32) -> u32 {
+    item_height / 2
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 { format!("1 {}", noun) } else { format!("{} {}s", n, noun) }
+}
+
+/// Quotes a CSV field when needed.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn upper_value(item: &config::Item) -> f64 {
+    match item.error {
+        Some((_, high)) => item.value + high,
+        None => item.value,
+    }
+}
+
+fn overflow_marker_width(bar_height: u32) -> u32 {
+    bar_height / 3
+}
+
+/// Returns the second largest value when the largest one is an outlier.
+fn find_outlier_next(values: &[f64]) -> Option<f64> {
+    let mut list = values.to_vec();
+    list.sort_by(|a, b| b.partial_cmp(a).unwrap_or(cmp::Ordering::Equal));
+
+    match (list.first(), list.get(1)) {
+        (Some(&max), Some(&next)) if next > 0.0 && max > next * OUTLIER_FACTOR => Some(next),
+        _ => None,
+    }
+}
+
+/// Returns an axis maximum and a distance between ticks.
+///
+/// The step is always a 1, 2, 2.5 or 5 multiplied by a power of 10,
+/// unless set explicitly. The maximum is a multiple of the step, unless set explicitly.
+fn calc_axis(min: f64, value: f64, max_value: Option<f64>, tick_count: u32,
+             tick_step: Option<f64>) -> (f64, f64)
+{
+    debug_assert!(tick_count >= 2);
+
+    if let Some(step) = tick_step {
+        let max = match max_value {
+            Some(mv) => mv,
+            None => round_to_step(((value / step).ceil() * step).max(min + step), step),
+        };
+
+        return (max, step);
+    }
+
+    match max_value {
+        Some(mv) => (mv, nice_step((mv - min) / (tick_count - 1) as f64)),
+        None if min == 0.0 => {
+            // all values can be zero
+            let max = if value > 0.0 { calc_max_value(value) } else { 1.0 };
+            let step = nice_step(max / (tick_count - 1) as f64);
+            (round_to_step((max / step).ceil() * step, step), step)
+        }
+        None => {
+            // all values can be smaller than the minimum
+            let range = if value > min { value - min } else { min.abs().max(1.0) };
+            let step = nice_step(range / (tick_count - 1) as f64);
+            (round_to_step(((min + range) / step).ceil() * step, step), step)
+        }
     }
 }
 
+/// Returns an axis minimum, maximum and a distance between ticks for durations and dates.
+///
+/// The step is a round time interval, like 15 minutes or 6 hours, unless set explicitly.
+/// The minimum and the maximum are multiples of the step, unless set explicitly.
+fn calc_time_axis(min: f64, value: f64, min_value: Option<f64>, max_value: Option<f64>,
+                  tick_count: u32, tick_step: Option<f64>) -> (f64, f64, f64)
+{
+    debug_assert!(tick_count >= 2);
+
+    let from = min_value.unwrap_or(min);
+    let to = max_value.unwrap_or(value);
+    // all values can be at the same time
+    let range = if to > from { to - from } else { 1.0 };
+
+    let step = match tick_step {
+        Some(step) => step,
+        None => nice_time_step(range / (tick_count - 1) as f64),
+    };
+
+    let min = match min_value {
+        Some(mv) => mv,
+        None => round_to_step((min / step).floor() * step, step),
+    };
+
+    let max = match max_value {
+        Some(mv) => mv,
+        None => round_to_step(((value / step).ceil() * step).max(min + step), step),
+    };
+
+    (min, max, step)
+}
+
+/// Returns a time interval from `TIME_STEPS` or a multiple of weeks closest to the value.
+fn nice_time_step(value: f64) -> f64 {
+    debug_assert!(value > 0.0);
+
+    if value < TIME_STEPS[0] {
+        return nice_step(value);
+    }
+
+    if value > WEEK {
+        // 2.5 weeks is not a nice interval
+        return WEEK * nice_step_from(value / WEEK, &[1.0, 2.0, 5.0, 10.0]);
+    }
+
+    let mut nice = TIME_STEPS[0];
+    for n in TIME_STEPS {
+        // compare ratios, like in nice_step
+        if (n / value).ln().abs() < (nice / value).ln().abs() {
+            nice = *n;
+        }
+    }
+
+    nice
+}
+
+/// Returns a 1, 2, 2.5, 5 or 10 multiplied by a power of 10 closest to the value.
+fn nice_step(value: f64) -> f64 {
+    nice_step_from(value, &[1.0, 2.0, 2.5, 5.0, 10.0])
+}
+
+/// Returns one of the fractions multiplied by a power of 10 closest to the value.
+///
+/// The fractions must be in the [1, 10] range.
+fn nice_step_from(value: f64, fractions: &[f64]) -> f64 {
+    debug_assert!(value > 0.0);
+
+    let exp = value.log10().floor();
+    let base = 10f64.powf(exp);
+    let fraction = value / base;
+
+    let mut nice = fractions[0];
+    for n in fractions {
+        // compare ratios, so 1.5 is closer to 2 than to 1
+        if (n / fraction).ln().abs() < (nice / fraction).ln().abs() {
+            nice = *n;
+        }
+    }
+
+    if exp < 0.0 {
+        // dividing by an exact power of 10 is more precise
+        nice / 10f64.powf(-exp)
+    } else {
+        nice * base
+    }
+}
+
+/// Rounds the value to the number of decimals used by the step.
+fn round_to_step(value: f64, step: f64) -> f64 {
+    let mut decimals = 0;
+    while decimals < 15 {
+        let n = step * 10f64.powi(decimals);
+        if (n.round() - n).abs() < 1e-9 {
+            break;
+        }
+
+        decimals += 1;
+    }
+
+    let k = 10f64.powi(decimals);
+    (value * k).round() / k
+}
+
+/// Returns multiples of the step between the minimum and the maximum.
+fn gen_ticks_list(min_value: f64, max_value: f64, step: f64) -> Vec<f64> {
+    let mut v = Vec::new();
+    // allow an error of the float division
+    let mut i = (min_value / step - 1e-9).ceil() as i64;
+    loop {
+        let n = round_to_step(i as f64 * step, step);
+        // allow an error of the float multiplication
+        if n > max_value + step * 1e-9 || v.len() == MAX_TICKS_COUNT as usize {
+            break;
+        }
+
+        v.push(n);
+        i += 1;
+    }
+
+    v
+}
+
+/// Returns a logarithmic axis minimum and maximum.
+///
+/// Both are powers of the base, unless set explicitly.
+fn calc_log_axis(min: f64, max: f64, min_value: Option<f64>, max_value: Option<f64>, base: f64)
+    -> (f64, f64)
+{
+    debug_assert!(min > 0.0);
+
+    let min = match min_value {
+        Some(mv) => mv,
+        None => base.powi(log_floor(min, base)),
+    };
+    let max = match max_value {
+        Some(mv) => mv,
+        None => calc_log_max_value(max, base),
+    };
+
+    if max > min {
+        (min, max)
+    } else {
+        (min, min * base)
+    }
+}
+
+/// Rounds the value up to the next power of the base.
+fn calc_log_max_value(value: f64, base: f64) -> f64 {
+    base.powi(log_ceil(value, base))
+}
+
+// 'log' is not precise, so 1000.0.log(10.0) can be 2.9999999999999996
+fn log_floor(value: f64, base: f64) -> i32 {
+    let n = value.log(base).floor() as i32;
+    if base.powi(n + 1) <= value * (1.0 + 1e-9) { n + 1 } else { n }
+}
+
+fn log_ceil(value: f64, base: f64) -> i32 {
+    let n = value.log(base).ceil() as i32;
+    if base.powi(n - 1) >= value * (1.0 - 1e-9) { n - 1 } else { n }
+}
+
+/// Returns powers of the base between the minimum and the maximum.
+fn gen_log_ticks_list(min_value: f64, max_value: f64, base: f64) -> Vec<f64> {
+    (log_ceil(min_value, base)..=log_floor(max_value, base))
+        .take(MAX_TICKS_COUNT as usize)
+        .map(|n| base.powi(n))
+        .collect()
+}
+
+/// Returns intermediate values between powers of the base, like 2, 3, ..., 9 for base 10.
+///
+/// Non-integer bases don't have minor ticks.
+fn gen_log_minor_ticks_list(min_value: f64, max_value: f64, base: f64) -> Vec<f64> {
+    let mut v = Vec::new();
+    if base.fract() != 0.0 {
+        return v;
+    }
+
+    for n in log_floor(min_value, base)..log_ceil(max_value, base) {
+        let p = base.powi(n);
+        for k in 2..(base as u32) {
+            let value = k as f64 * p;
+            if value > min_value && value < max_value && v.len() < MAX_TICKS_COUNT as usize {
+                v.push(value);
+            }
+        }
+    }
+
+    v
+}
+
 fn calc_max_value(value: f64) -> f64 {
     // this function trying to mimic 'google charts' algorithm
 
@@ -296,16 +1305,16 @@ fn calc_max_value(value: f64) -> f64 {
 
     let v2 = match v1 {
         100 => 100,
-        101...119 => 120,
-        120...149 => 160,
-        150...200 => 200,
-        201...240 => 240,
-        241...299 => 300,
-        300...400 => 400,
-        401...449 => 500,
-        450...599 => 600,
-        600...749 => 800,
-        750...999 => 1000,
+        101..=119 => 120,
+        120..=149 => 160,
+        150..=200 => 200,
+        201..=240 => 240,
+        241..=299 => 300,
+        300..=400 => 400,
+        401..=449 => 500,
+        450..=599 => 600,
+        600..=749 => 800,
+        750..=999 => 1000,
         _ => unreachable!(),
     };
 
@@ -319,7 +1328,46 @@ fn calc_max_value(value: f64) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::calc_max_value;
+    use super::{calc_max_value, calc_axis, nice_step, gen_ticks_list, calc_log_axis,
+                gen_log_ticks_list, gen_log_minor_ticks_list, find_outlier_next,
+                calc_time_axis, nice_time_step, csv_field, AnnotationPos, BarsLayout};
+    use svgdom::{AttributeId as AId, AttributeValue, Document, ElementId as EId, Node};
+
+    use layouts::layout::{CalcLayout, DrawLayout};
+    use config::Config;
+    use font::{FontData, FontMetrics};
+    use serde_json;
+
+    /// Calculates a layout of the config using the system font.
+    fn calc_layout<F: Fn(&BarsLayout)>(data: &str, check: F) {
+        let value: serde_json::Value = serde_json::from_str(data).unwrap();
+        let sf = FontData::system_font().unwrap();
+        let config = Config::from_value(&value, &sf).unwrap();
+        let font = &config.items_font;
+        let fm = FontMetrics::from_font(&font.family, font.size).unwrap();
+
+        let mut lay = BarsLayout::new(&config);
+        lay.calc_layout(&fm);
+        check(&lay);
+    }
+
+    /// Calculates and draws a layout of the config using the system font.
+    fn draw_layout<F: Fn(&BarsLayout, &Node)>(data: &str, check: F) {
+        let value: serde_json::Value = serde_json::from_str(data).unwrap();
+        let sf = FontData::system_font().unwrap();
+        let config = Config::from_value(&value, &sf).unwrap();
+        let font = &config.items_font;
+        let fm = FontMetrics::from_font(&font.family, font.size).unwrap();
+
+        let mut lay = BarsLayout::new(&config);
+        lay.calc_layout(&fm);
+
+        let mut doc = Document::new();
+        let mut svg = doc.create_element(EId::Svg);
+        doc.append(&svg);
+        lay.draw_layout(&fm, 0, 0, &mut svg);
+        check(&lay, &svg);
+    }
 
     macro_rules! test {
         ($name:ident, $value:expr, $result:expr) => (
@@ -375,4 +1423,182 @@ mod tests {
             calc_max_value(i as f64 / 10.0);
         }
     }
+
+    #[test]
+    fn nice_step_1() {
+        assert_eq!(nice_step(1.0), 1.0);
+        assert_eq!(nice_step(1.4), 1.0);
+        assert_eq!(nice_step(1.5), 2.0);
+        assert_eq!(nice_step(2.3), 2.5);
+        assert_eq!(nice_step(4.0), 5.0);
+        assert_eq!(nice_step(12.5), 10.0);
+        assert_eq!(nice_step(30.0), 25.0);
+        assert_eq!(nice_step(0.075), 0.1);
+        assert_eq!(nice_step(0.03), 0.025);
+        assert_eq!(nice_step(1500.0), 2000.0);
+    }
+
+    #[test]
+    fn calc_axis_1() {
+        assert_eq!(calc_axis(0.0, 42.0, None, 5, None), (50.0, 10.0));
+        assert_eq!(calc_axis(0.0, 89.1, None, 5, None), (100.0, 25.0));
+        assert_eq!(calc_axis(0.0, 14.0, None, 5, None), (20.0, 5.0));
+        assert_eq!(calc_axis(0.0, 0.3, None, 5, None), (0.4, 0.1));
+        assert_eq!(calc_axis(0.0, 42.0, Some(100.0), 5, None), (100.0, 25.0));
+        assert_eq!(calc_axis(0.0, 42.0, None, 5, Some(15.0)), (45.0, 15.0));
+        assert_eq!(calc_axis(0.0, 42.0, None, 3, None), (50.0, 25.0));
+        assert_eq!(calc_axis(40.0, 48.0, None, 5, None), (48.0, 2.0));
+        assert_eq!(calc_axis(40.0, 57.0, Some(60.0), 5, None), (60.0, 5.0));
+        assert_eq!(calc_axis(0.0, 0.0, None, 5, None), (1.0, 0.25));
+    }
+
+    #[test]
+    fn gen_ticks_list_1() {
+        assert_eq!(gen_ticks_list(0.0, 0.4, 0.1), vec![0.0, 0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(gen_ticks_list(0.0, 0.3, 0.05), vec![0.0, 0.05, 0.1, 0.15, 0.2, 0.25, 0.3]);
+        assert_eq!(gen_ticks_list(0.0, 90.0, 25.0), vec![0.0, 25.0, 50.0, 75.0]);
+        assert_eq!(gen_ticks_list(35.0, 60.0, 10.0), vec![40.0, 50.0, 60.0]);
+    }
+
+    #[test]
+    fn calc_log_axis_1() {
+        assert_eq!(calc_log_axis(3.0, 420.0, None, None, 10.0), (1.0, 1000.0));
+        assert_eq!(calc_log_axis(10.0, 1000.0, None, None, 10.0), (10.0, 1000.0));
+        assert_eq!(calc_log_axis(0.05, 20.0, None, None, 10.0), (0.01, 100.0));
+        assert_eq!(calc_log_axis(3.0, 20.0, None, None, 2.0), (2.0, 32.0));
+        assert_eq!(calc_log_axis(10.0, 10.0, None, None, 10.0), (10.0, 100.0));
+        assert_eq!(calc_log_axis(3.0, 420.0, None, Some(500.0), 10.0), (1.0, 500.0));
+    }
+
+    #[test]
+    fn gen_log_ticks_list_1() {
+        assert_eq!(gen_log_ticks_list(1.0, 1000.0, 10.0), vec![1.0, 10.0, 100.0, 1000.0]);
+        assert_eq!(gen_log_ticks_list(2.0, 32.0, 2.0), vec![2.0, 4.0, 8.0, 16.0, 32.0]);
+        assert_eq!(gen_log_ticks_list(1.0, 500.0, 10.0), vec![1.0, 10.0, 100.0]);
+    }
+
+    #[test]
+    fn gen_log_minor_ticks_list_1() {
+        assert_eq!(gen_log_minor_ticks_list(1.0, 10.0, 10.0),
+                   vec![2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(gen_log_minor_ticks_list(1.0, 27.0, 3.0), vec![2.0, 6.0, 18.0]);
+        assert!(gen_log_minor_ticks_list(2.0, 32.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn find_outlier_next_1() {
+        assert_eq!(find_outlier_next(&[10.0, 500.0, 20.0]), Some(20.0));
+        assert_eq!(find_outlier_next(&[10.0, 30.0, 20.0]), None);
+        assert_eq!(find_outlier_next(&[500.0, 500.0, 20.0]), None);
+        assert_eq!(find_outlier_next(&[500.0]), None);
+    }
+
+    #[test]
+    fn nice_time_step_1() {
+        assert_eq!(nice_time_step(0.18), 0.2);
+        assert_eq!(nice_time_step(40.0), 30.0);
+        assert_eq!(nice_time_step(1000.0), 900.0);
+        assert_eq!(nice_time_step(5000.0), 3600.0);
+        assert_eq!(nice_time_step(1500000.0), 1209600.0);
+        assert_eq!(nice_time_step(2600000.0), 3024000.0);
+    }
+
+    #[test]
+    fn hidden_annotation_1() {
+        // value texts are wider than the short bar
+        for annotation in &["\"hide\"", "{ \"show\": false, \"placement\": \"center\" }"] {
+            for orientation in &["horizontal", "vertical"] {
+                let data = format!("{{
+                    \"items\": [{{ \"name\": \"a\", \"value\": 1 }},
+                                  {{ \"name\": \"b\", \"value\": 1000 }}],
+                    \"annotation\": {},
+                    \"orientation\": \"{}\"
+                }}", annotation, orientation);
+
+                calc_layout(&data, |lay| {
+                    assert!(lay.bars.iter().all(|b| b.annotation_pos == AnnotationPos::Hidden));
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn fit_annotations_1() {
+        // long annotations after the longest bars, with an automatic and a fixed width
+        for width in &["", ", \"width\": 100"] {
+            let data = format!("{{
+                \"items\": [{{ \"name\": \"a\", \"value\": 97 }},
+                              {{ \"name\": \"b\", \"value\": 100 }},
+                              {{ \"name\": \"c\", \"value\": 3 }}],
+                \"annotation\": {{
+                    \"placement\": \"outside\",
+                    \"template\": \"{{value}} requests per second\"
+                }},
+                \"hor_axis\": {{ \"max_value\": 100{} }}
+            }}", width);
+
+            calc_layout(&data, |lay| {
+                for bar in lay.bars.iter().filter(|b| b.annotation_pos == AnnotationPos::Outside) {
+                    assert!(bar.annotation_rect.right() <= lay.lay.size.w);
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn summary_1() {
+        calc_layout("{
+            \"items\": [{ \"name\": \"a\", \"start\": 8, \"end\": 10 },
+                          { \"name\": \"b\", \"start\": 1, \"end\": 5 }]
+        }", |lay| {
+            assert_eq!(lay.summary(),
+                       "Bar chart with 2 items. Values range from 8–10 (a) to 1–5 (b).");
+        });
+    }
+
+    #[test]
+    fn zero_values_1() {
+        calc_layout("{
+            \"items\": [{ \"name\": \"a\", \"value\": 0 }, { \"name\": \"b\", \"value\": 0 }]
+        }", |lay| {
+            assert_eq!(lay.max_value, 1.0);
+            assert!(lay.bars.iter().all(|bar| bar.r.w == 0));
+        });
+    }
+
+    #[test]
+    fn wide_lines_1() {
+        // lines are wider than bars and the distance to the plot edge
+        draw_layout("{
+            \"items\": [{ \"name\": \"a\", \"value\": 5, \"error\": 1 }],
+            \"reference_lines\": [{ \"value\": 0 }],
+            \"theme\": { \"whisker_width\": 100, \"reference_line_width\": 40 }
+        }", |lay, svg| {
+            let num = |node: &Node, id| match node.attributes().get_value(id) {
+                Some(&AttributeValue::Number(n)) => n,
+                v => panic!("unexpected {:?}: {:?}", id, v),
+            };
+
+            for rect in svg.descendants().filter(|n| n.is_tag_name(EId::Rect)) {
+                assert!(num(&rect, AId::X) + num(&rect, AId::Width) <= lay.lay.size.w as f64);
+                assert!(num(&rect, AId::Y) + num(&rect, AId::Height) <= lay.lay.size.h as f64);
+            }
+        });
+    }
+
+    #[test]
+    fn csv_field_1() {
+        assert_eq!(csv_field("xml"), "xml");
+        assert_eq!(csv_field("1,5 ms"), "\"1,5 ms\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn calc_time_axis_1() {
+        assert_eq!(calc_time_axis(0.0, 1.7, None, None, 5, None), (0.0, 2.0, 0.5));
+        assert_eq!(calc_time_axis(0.0, 200.0, None, None, 5, None), (0.0, 240.0, 60.0));
+        // dates
+        assert_eq!(calc_time_axis(86400.0 + 5000.0, 86400.0 + 30000.0, None, None, 5, None),
+                   (86400.0, 86400.0 + 36000.0, 7200.0));
+    }
 }
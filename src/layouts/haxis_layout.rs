@@ -3,39 +3,61 @@ use svgdom::{AttributeId as AId, Node};
 use super::layout::{Layout, CalcLayout, DrawLayout};
 use super::adaptors::Adaptor;
 use font::FontMetrics;
+use theme::Theme;
 
+/// The value axis title. Below bars or on the left of columns.
 pub struct HAxisLayout<'a> {
     pub lay: Layout,
     title: &'a str,
     title_width: u32,
+    theme: &'a Theme<'a>,
+    vertical: bool,
 }
 
 impl<'a> HAxisLayout<'a> {
-    pub fn new(title: &'a str) -> HAxisLayout<'a> {
+    pub fn new(title: &'a str, theme: &'a Theme<'a>, vertical: bool) -> HAxisLayout<'a> {
         HAxisLayout {
             lay: Layout::default(),
-            title: title,
+            title,
             title_width: 0,
+            theme,
+            vertical,
         }
     }
 }
 
 impl<'a> CalcLayout for HAxisLayout<'a> {
     fn calc_layout(&mut self, fm: &FontMetrics) {
-        let bbox = fm.text_bbox(&self.title);
-        self.lay.size.h = fm.full_height();
+        let bbox = fm.text_bbox(self.title);
         self.title_width = bbox.w;
+
+        // the other side will be set by MainLayout
+        if self.vertical {
+            self.lay.size.w = fm.full_height();
+        } else {
+            self.lay.size.h = fm.full_height();
+        }
     }
 }
 
 impl<'a> DrawLayout for HAxisLayout<'a> {
     fn draw_layout(&self, fm: &FontMetrics, x: u32, y: u32, root: &mut Node) {
         // should be set by MainLayout
-        debug_assert!(self.lay.size.w > 0);
+        debug_assert!(self.lay.size.w > 0 && self.lay.size.h > 0);
 
-        let tx = x + (self.lay.size.w - self.title_width) / 2;
-        let mut text = root.append_text(self.title, tx, y + fm.height(), fm);
-        text.set_attribute((AId::FontStyle, "italic"));
+        let mut text = if self.vertical {
+            // read from bottom to top
+            let tx = x + fm.height();
+            let ty = y + (self.lay.size.h + self.title_width) / 2;
+            let mut text = root.append_text(self.title, tx, ty, fm);
+            text.set_attribute((AId::Transform, format!("rotate(-90 {} {})", tx, ty)));
+            text
+        } else {
+            let tx = x + (self.lay.size.w - self.title_width) / 2;
+            root.append_text(self.title, tx, y + fm.height(), fm)
+        };
+        text.set_attribute((AId::FontStyle, self.theme.axis_title_font_style));
+        text.set_attribute((AId::Fill, self.theme.text_color));
 
         if self.lay.debug {
             let mut r = root.append_rect(x, y, self.lay.size.w, self.lay.size.h);
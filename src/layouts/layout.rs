@@ -11,19 +11,9 @@ pub trait DrawLayout {
     fn draw_layout(&self, font: &FontMetrics, x: u32, y: u32, root: &mut Node);
 }
 
-#[derive(Debug)]
+#[derive(Default, Debug)]
 pub struct Layout {
     pub size: Size,
     pub margins: Margins,
     pub debug: bool,
 }
-
-impl Default for Layout {
-    fn default() -> Layout {
-        Layout {
-            size: Size::default(),
-            margins: Margins::default(),
-            debug: false,
-        }
-    }
-}
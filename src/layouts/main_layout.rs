@@ -1,15 +1,19 @@
-use svgdom::Node;
+use std::cmp;
+
+use svgdom::{AttributeId as AId, Node};
 
 use super::bars_layout::BarsLayout;
 use super::title_layout::TitleLayout;
 use super::haxis_layout::HAxisLayout;
 use super::vaxis_layout::VAxisLayout;
 use super::layout::{CalcLayout, DrawLayout};
+use super::adaptors::Adaptor;
 use super::Size;
 use font::FontMetrics;
-use config;
+use config::{self, Orientation};
 
 pub struct MainLayout<'a> {
+    config: &'a config::Config<'a>,
     size: Size,
     bars_lay: BarsLayout<'a>,
     title_lay: Option<TitleLayout<'a>>,
@@ -19,27 +23,23 @@ pub struct MainLayout<'a> {
 
 impl<'a> MainLayout<'a> {
     pub fn new(config: &'a config::Config<'a>) -> MainLayout<'a> {
-        let tl = match config.title {
-            Some(title) => Some(TitleLayout::new(title)),
-            None => None,
-        };
+        let tl = config.title.map(|title| TitleLayout::new(title, &config.theme));
 
-        let hal = match &config.hor_axis {
-            &Some(ref axis) => {
-                match axis.title {
-                    Some(title) => Some(HAxisLayout::new(title)),
-                    None => None,
-                }
+        let vertical = config.orientation == Orientation::Vertical;
+        let hal = match config.hor_axis {
+            Some(ref axis) => {
+                axis.title.map(|title| HAxisLayout::new(title, &config.theme, vertical))
             }
-            &None => None,
+            None => None,
         };
 
         MainLayout {
+            config,
             size: Size::default(),
-            bars_lay: BarsLayout::new(&config),
+            bars_lay: BarsLayout::new(config),
             title_lay: tl,
             haxis_lay: hal,
-            vaxis_lay: VAxisLayout::new(&config),
+            vaxis_lay: VAxisLayout::new(config),
         }
     }
 
@@ -47,14 +47,12 @@ impl<'a> MainLayout<'a> {
         self.bars_lay.lay.debug = flag;
         self.vaxis_lay.lay.debug = flag;
 
-        match &mut self.title_lay {
-            &mut Some(ref mut l) => l.lay.debug = flag,
-            &mut None => {}
+        if let Some(ref mut l) = self.title_lay {
+            l.lay.debug = flag;
         }
 
-        match &mut self.haxis_lay {
-            &mut Some(ref mut l) => l.lay.debug = flag,
-            &mut None => {}
+        if let Some(ref mut l) = self.haxis_lay {
+            l.lay.debug = flag;
         }
     }
 
@@ -65,6 +63,41 @@ impl<'a> MainLayout<'a> {
     pub fn height(&self) -> u32 {
         self.size.h
     }
+
+    /// Adds texts for screen readers to the root element.
+    fn draw_description(&self, root: &mut Node) {
+        let a11y = &self.config.accessibility;
+        let mut labels = Vec::new();
+
+        if let Some(title) = self.config.title {
+            root.append_title(title).set_id("barh-title");
+            labels.push("barh-title");
+        }
+
+        let summary = match a11y.summary {
+            Some(text) => text.to_string(),
+            None => self.bars_lay.summary(),
+        };
+        root.append_desc(&summary).set_id("barh-summary");
+        labels.push("barh-summary");
+
+        root.set_attribute(("role", "img"));
+        root.set_attribute(("aria-labelledby", labels.join(" ")));
+
+        if a11y.data_table {
+            root.append_desc(&self.bars_lay.data_table()).set_id("barh-data");
+            root.set_attribute(("aria-describedby", "barh-data"));
+        }
+    }
+
+    /// Returns a width on the left of the bars layout for columns.
+    ///
+    /// The value axis title is there and rotated item names can stick out.
+    fn columns_left(&self) -> u32 {
+        let title_w = self.haxis_lay.as_ref().map(|l| l.lay.size.w).unwrap_or(0);
+        // item names already start at the bars layout origin
+        cmp::max(title_w, self.vaxis_lay.lay.margins.left)
+    }
 }
 
 impl<'a> CalcLayout for MainLayout<'a> {
@@ -73,60 +106,148 @@ impl<'a> CalcLayout for MainLayout<'a> {
 
         let mut h = 0;
 
-        match &mut self.title_lay {
-            &mut Some(ref mut l) => {
-                l.calc_layout(fm);
-                h += l.lay.size.h;
-            }
-            &mut None => {}
+        if let Some(ref mut l) = self.title_lay {
+            l.calc_layout(fm);
+            h += l.lay.size.h;
         }
 
         // TODO: maybe stretch bars to title width
         h += self.bars_lay.lay.size.h;
 
-        match &mut self.haxis_lay {
-            &mut Some(ref mut l) => {
-                l.calc_layout(fm);
-                l.lay.size.w = self.bars_lay.lay.size.w;
-                h += l.lay.size.h;
-            }
-            &mut None => {}
+        if let Some(ref mut l) = self.haxis_lay {
+            l.calc_layout(fm);
         }
 
-        for bar in self.bars_lay.bars.iter() {
-            self.vaxis_lay.ticks.push(bar.annotation_bbox.y as u32);
-        }
+        self.vaxis_lay.ticks = self.bars_lay.item_ticks();
+        self.vaxis_lay.headers = self.bars_lay.group_headers.clone();
 
         self.vaxis_lay.calc_layout(fm);
-        self.vaxis_lay.lay.size.h = self.bars_lay.lay.size.h;
 
-        self.size.w = self.bars_lay.lay.size.w + self.vaxis_lay.lay.size.w;
-        self.size.h = h;
+        let p = self.config.padding;
+        match self.config.orientation {
+            Orientation::Horizontal => {
+                if let Some(ref mut l) = self.haxis_lay {
+                    l.lay.size.w = self.bars_lay.lay.size.w;
+                    h += l.lay.size.h;
+                }
+
+                self.vaxis_lay.lay.size.h = self.bars_lay.lay.size.h;
+
+                self.size.w = self.bars_lay.lay.size.w + self.vaxis_lay.lay.size.w + p * 2;
+                self.size.h = h + p * 2;
+            }
+            Orientation::Vertical => {
+                // names are below columns and the axis title is on the left
+                self.vaxis_lay.lay.size.w = self.bars_lay.lay.size.w;
+                if let Some(ref mut l) = self.haxis_lay {
+                    l.lay.size.h = self.bars_lay.lay.size.h;
+                }
+                h += self.vaxis_lay.lay.size.h;
+
+                self.size.w = self.bars_lay.lay.size.w + self.columns_left() + p * 2;
+                self.size.h = h + p * 2;
+            }
+        }
     }
 }
 
 impl<'a> DrawLayout for MainLayout<'a> {
     fn draw_layout(&self, fm: &FontMetrics, x: u32, y: u32, root: &mut Node) {
-        let tx = x + self.vaxis_lay.lay.size.w;
+        // must be the first children
+        self.draw_description(root);
+
+        if self.config.theme.background != "none" {
+            // SVG size is bigger by one pixel
+            let mut bg = root.append_rect(x, y, self.size.w + 1, self.size.h + 1);
+            bg.set_attribute((AId::Fill, self.config.theme.background));
+        }
+
+        let x = x + self.config.padding;
+        let y = y + self.config.padding;
+
+        let tx = match self.config.orientation {
+            Orientation::Horizontal => x + self.vaxis_lay.lay.size.w,
+            Orientation::Vertical => x + self.columns_left(),
+        };
         let mut ty = y;
 
-        match &self.title_lay {
-            &Some(ref l) => {
-                l.draw_layout(fm, tx + self.bars_lay.lay.margins.left, ty, root);
-                ty += l.lay.size.h;
-            }
-            &None => {}
+        if let Some(ref l) = self.title_lay {
+            l.draw_layout(fm, tx + self.bars_lay.lay.margins.left, ty, root);
+            ty += l.lay.size.h;
         }
 
-        // draw after title
-        self.vaxis_lay.draw_layout(fm, x, ty, root);
+        match self.config.orientation {
+            Orientation::Horizontal => {
+                // draw after title
+                self.vaxis_lay.draw_layout(fm, x, ty, root);
 
-        self.bars_lay.draw_layout(fm, tx, ty, root);
-        ty += self.bars_lay.lay.size.h;
+                self.bars_lay.draw_layout(fm, tx, ty, root);
+                ty += self.bars_lay.lay.size.h;
 
-        match &self.haxis_lay {
-            &Some(ref l) => l.draw_layout(fm, tx, ty, root),
-            &None => {}
+                if let Some(ref l) = self.haxis_lay {
+                    l.draw_layout(fm, tx, ty, root);
+                }
+            }
+            Orientation::Vertical => {
+                if let Some(ref l) = self.haxis_lay {
+                    l.draw_layout(fm, x, ty, root);
+                }
+
+                self.bars_lay.draw_layout(fm, tx, ty, root);
+                ty += self.bars_lay.lay.size.h;
+
+                self.vaxis_lay.draw_layout(fm, tx, ty, root);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use svgdom::{AttributeId as AId, AttributeValue, Document, ElementId as EId};
+
+    use super::MainLayout;
+    use layouts::layout::{CalcLayout, DrawLayout};
+    use config::Config;
+    use font::{FontData, FontMetrics};
+    use serde_json;
+
+    #[test]
+    fn rotated_names_1() {
+        // the first name is long enough to stick out on the left
+        let value: serde_json::Value = serde_json::from_str("{
+            \"items\": [{ \"name\": \"a very long first name\", \"value\": 12 },
+                          { \"name\": \"beta long name here\", \"value\": 250 }],
+            \"orientation\": \"vertical\",
+            \"padding\": 10
+        }").unwrap();
+        let sf = FontData::system_font().unwrap();
+        let config = Config::from_value(&value, &sf).unwrap();
+        let font = &config.items_font;
+        let fm = FontMetrics::from_font(&font.family, font.size).unwrap();
+
+        let mut lay = MainLayout::new(&config);
+        lay.calc_layout(&fm);
+
+        let mut doc = Document::new();
+        let mut svg = doc.create_element(EId::Svg);
+        doc.append(&svg);
+        lay.draw_layout(&fm, 0, 0, &mut svg);
+
+        let mut rotated = 0;
+        for node in svg.descendants().filter(|n| n.is_tag_name(EId::Text)) {
+            let attrs = node.attributes();
+            if !attrs.contains(AId::Transform) {
+                continue;
+            }
+
+            rotated += 1;
+            match attrs.get_value(AId::X) {
+                // names must not overlap the padding
+                Some(&AttributeValue::Number(x)) => assert!(x >= 10.0, "x: {}", x),
+                v => panic!("unexpected x: {:?}", v),
+            }
         }
+        assert_eq!(rotated, 2);
     }
 }
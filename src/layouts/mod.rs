@@ -9,33 +9,24 @@ mod vaxis_layout;
 mod layout;
 mod adaptors;
 
-#[derive(Debug)]
+#[derive(Default, Debug)]
 pub struct Size {
     pub w: u32,
     pub h: u32,
 }
 
-impl Default for Size {
-    fn default() -> Size {
-        Size {
-            w: 0,
-            h: 0,
-        }
-    }
-}
-
 impl Size {
-    fn into_rect(&self, x: i32, y: i32) -> Rect {
+    fn to_rect(&self, x: i32, y: i32) -> Rect {
         Rect {
-            x: x,
-            y: y,
+            x,
+            y,
             w: self.w,
             h: self.h,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Default, Debug)]
 pub struct Rect {
     pub x: i32,
     pub y: i32,
@@ -43,25 +34,9 @@ pub struct Rect {
     pub h: u32,
 }
 
-impl Default for Rect {
-    fn default() -> Rect {
-        Rect {
-            x: 0,
-            y: 0,
-            w: 0,
-            h: 0,
-        }
-    }
-}
-
 impl Rect {
     fn new(x: i32, y: i32, w: u32, h: u32) -> Rect {
-        Rect {
-            x: x,
-            y: y,
-            w: w,
-            h: h,
-        }
+        Rect { x, y, w, h }
     }
 
     fn adjusted(&self, margins: &Margins) -> Rect {
@@ -74,25 +49,14 @@ impl Rect {
     }
 
     fn right(&self) -> u32 {
-        self.x.abs() as u32 + self.w
+        self.x.unsigned_abs() + self.w
     }
 }
 
-#[derive(Debug)]
+#[derive(Default, Debug)]
 pub struct Margins {
     pub left: u32,
     pub top: u32,
     pub right: u32,
     pub bottom: u32,
 }
-
-impl Default for Margins {
-    fn default() -> Margins {
-        Margins {
-            left: 0,
-            top: 0,
-            right: 0,
-            bottom: 0,
-        }
-    }
-}
@@ -1,37 +1,136 @@
 use std::cmp;
+use std::f32::consts::FRAC_1_SQRT_2;
 
 use svgdom::{AttributeId as AId, Node};
 
 use super::layout::{Layout, CalcLayout, DrawLayout};
 use super::adaptors::Adaptor;
 use font::FontMetrics;
-use config;
+use config::{self, Orientation};
 
+static SPACING: u32 = 4;
+
+/// Item names. On the left of bars or below columns.
 pub struct VAxisLayout<'a> {
     pub lay: Layout,
     config: &'a config::Config<'a>,
+    /// Baselines of item names. Centers for columns.
     pub ticks: Vec<u32>,
+    /// Baselines of the group headers. Centers for columns.
+    pub headers: Vec<u32>,
+    /// Column names are rotated when they don't fit into a column pitch.
+    rotated: bool,
 }
 
 impl<'a> VAxisLayout<'a> {
     pub fn new(config: &'a config::Config<'a>) -> VAxisLayout<'a> {
         VAxisLayout {
             lay: Layout::default(),
-            config: config,
+            config,
             ticks: Vec::new(),
+            headers: Vec::new(),
+            rotated: false,
+        }
+    }
+
+    fn calc_columns_layout(&mut self, fm: &FontMetrics) {
+        let widths: Vec<u32> = self.config.items.iter().map(|i| fm.text_bbox(i.name).w).collect();
+        let max_w = widths.iter().cloned().max().unwrap_or(0);
+
+        // ticks list should be set by MainLayout
+        let pitch = self.ticks.windows(2).map(|t| t[1] - t[0]).min().unwrap_or(u32::MAX);
+        self.rotated = max_w + SPACING > pitch;
+
+        let names_h = if self.rotated {
+            // a text end is at the column center, so the first names can stick out on the left
+            let offset = fm.height() / 3;
+            for (w, tick) in widths.iter().zip(self.ticks.iter()) {
+                let overhang = rotated_len(*w).saturating_sub(tick + offset);
+                self.lay.margins.left = cmp::max(overhang, self.lay.margins.left);
+            }
+
+            SPACING + fm.height() * 2 / 3 + rotated_len(max_w) + fm.full_height() - fm.height()
+        } else {
+            SPACING + fm.full_height()
+        };
+
+        let headers_h = if self.config.groups.is_empty() { 0 } else { fm.full_height() };
+        self.lay.size.h = names_h + headers_h;
+
+        // width will be set by MainLayout
+    }
+
+    fn draw_bars_layout(&self, fm: &FontMetrics, x: u32, y: u32, root: &mut Node) {
+        for (item, tick) in self.config.items.iter().zip(self.ticks.iter()) {
+            let bbox = fm.text_bbox(item.name);
+            let tx = x + self.lay.size.w - bbox.w;
+            let mut text = root.append_text(item.name, tx, y + *tick, fm);
+            text.set_attribute((AId::Fill, self.config.theme.text_color));
+
+            if self.lay.debug {
+                let dy = y + *tick - fm.height();
+                let mut r = root.append_rect(tx, dy, bbox.w, fm.full_height());
+                r.set_attribute((AId::Stroke, "red"));
+            }
+        }
+
+        // headers are aligned to the left, unlike item names
+        for (group, header) in self.config.groups.iter().zip(self.headers.iter()) {
+            let mut text = root.append_text(group.name, x, y + *header, fm);
+            text.set_attribute((AId::FontWeight, self.config.theme.group_header_font_weight));
+            text.set_attribute((AId::Fill, self.config.theme.text_color));
+        }
+    }
+
+    fn draw_columns_layout(&self, fm: &FontMetrics, x: u32, y: u32, root: &mut Node) {
+        for (item, tick) in self.config.items.iter().zip(self.ticks.iter()) {
+            let bbox = fm.text_bbox(item.name);
+            let mut text = if self.rotated {
+                let ex = x + tick + fm.height() / 3;
+                let ey = y + SPACING + fm.height() * 2 / 3;
+                // MainLayout reserves the overhang, but an overflow must not panic
+                let tx = ex.saturating_sub(rotated_len(bbox.w));
+                let ty = ey + rotated_len(bbox.w);
+                let mut text = root.append_text(item.name, tx, ty, fm);
+                text.set_attribute((AId::Transform, format!("rotate(-45 {} {})", tx, ty)));
+                text
+            } else {
+                root.append_text(item.name, x + tick - bbox.w / 2, y + SPACING + fm.height(), fm)
+            };
+            text.set_attribute((AId::Fill, self.config.theme.text_color));
+        }
+
+        // headers are in the last row
+        let ty = y + self.lay.size.h - fm.full_height() + fm.height();
+        for (group, header) in self.config.groups.iter().zip(self.headers.iter()) {
+            let bbox = fm.text_bbox(group.name);
+            let tx = (x + header).saturating_sub(bbox.w / 2);
+            let mut text = root.append_text(group.name, tx, ty, fm);
+            text.set_attribute((AId::FontWeight, self.config.theme.group_header_font_weight));
+            text.set_attribute((AId::Fill, self.config.theme.text_color));
         }
     }
 }
 
 impl<'a> CalcLayout for VAxisLayout<'a> {
     fn calc_layout(&mut self, fm: &FontMetrics) {
+        if self.config.orientation == Orientation::Vertical {
+            self.calc_columns_layout(fm);
+            return;
+        }
+
         let mut max_w = 0;
         for item in &self.config.items {
             let bbox = fm.text_bbox(item.name);
             max_w = cmp::max(bbox.w, max_w);
         }
 
-        self.lay.size.w = max_w + 4;
+        for group in &self.config.groups {
+            let bbox = fm.text_bbox(group.name);
+            max_w = cmp::max(bbox.w, max_w);
+        }
+
+        self.lay.size.w = max_w + SPACING;
 
         // height will be set by MainLayout
     }
@@ -43,16 +142,10 @@ impl<'a> DrawLayout for VAxisLayout<'a> {
         // ticks list should be set by MainLayout
         debug_assert!(!self.ticks.is_empty());
 
-        for (item, tick) in self.config.items.iter().zip(self.ticks.iter()) {
-            let bbox = fm.text_bbox(item.name);
-            let tx = x + self.lay.size.w - bbox.w;
-            root.append_text(item.name, tx, y + *tick, fm);
-
-            if self.lay.debug {
-                let dy = y + *tick - fm.height();
-                let mut r = root.append_rect(tx, dy, bbox.w, fm.full_height());
-                r.set_attribute((AId::Stroke, "red"));
-            }
+        if self.config.orientation == Orientation::Vertical {
+            self.draw_columns_layout(fm, x, y, root);
+        } else {
+            self.draw_bars_layout(fm, x, y, root);
         }
 
         if self.lay.debug {
@@ -61,3 +154,8 @@ impl<'a> DrawLayout for VAxisLayout<'a> {
         }
     }
 }
+
+/// Returns a horizontal or a vertical extent of a text rotated by 45 degrees.
+fn rotated_len(w: u32) -> u32 {
+    (w as f32 * FRAC_1_SQRT_2).ceil() as u32
+}
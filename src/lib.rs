@@ -1,18 +1,33 @@
 extern crate svgdom;
 extern crate serde_json;
 extern crate rusttype;
+extern crate serde_yaml;
+extern crate toml;
+extern crate csv;
 
 pub use error::{Error, BarhResult};
 
 pub mod font;
 pub mod layouts;
+pub mod color;
 pub mod config;
+pub mod data;
 pub mod error;
+pub mod input;
+pub mod number_format;
+pub mod palette;
+pub mod schema;
+pub mod theme;
+pub mod time;
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read};
 
 pub fn load_file(path: &str) -> BarhResult<Vec<u8>> {
+    read_file(path).map_err(|e| Error::IoError(path.to_string(), e))
+}
+
+fn read_file(path: &str) -> io::Result<Vec<u8>> {
     let mut file = File::open(path)?;
 
     let length = file.metadata()?.len() as usize;
@@ -1,11 +1,20 @@
-extern crate serde_json;
 extern crate svgdom;
+extern crate serde_json;
+extern crate getopts;
+extern crate glob;
+#[cfg(target_os = "linux")]
+extern crate inotify;
 extern crate barh;
 
 use std::env;
+use std::error::Error as StdError;
 use std::f64;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use serde_json::Value;
 
 use svgdom::{
     Document,
@@ -17,48 +26,478 @@ use svgdom::{
 
 use barh::font::{FontData, FontMetrics};
 use barh::config::Config;
+use barh::data;
+use barh::input;
+use barh::schema;
+use barh::theme;
 use barh::layouts::{MainLayout, CalcLayout, DrawLayout};
-use barh::load_file;
+use barh::{BarhResult, Error};
 
-macro_rules! main_try {
-    ($expr:expr) => (
-        match $expr {
-            Ok(c) => c,
-            Err(e) => {
-                println!("{:?}", e);
-                return;
-            }
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Svg,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "svg" => Some(OutputFormat::Svg),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match *self {
+            OutputFormat::Svg => "svg",
         }
-    )
+    }
+}
+
+struct Options {
+    input_format: Option<input::Format>,
+    output_format: OutputFormat,
+    check: bool,
+    watch: bool,
+    title: Option<String>,
+    width: Option<u32>,
+    font: Option<String>,
+    theme: Option<String>,
+    debug: bool,
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
+    let args: Vec<_> = env::args().skip(1).collect();
 
-    if args.len() != 3 {
-        println!("Usage:\n\tbarh config.json out.svg");
+    if args.len() == 1 && args[0] == "schema" {
+        println!("{:#}", schema::json_schema());
         return;
     }
 
+    let mut opts = getopts::Options::new();
+    opts.optopt("", "input-format", "Config format. Detected by extension by default.",
+                "json|yaml|toml|csv|tsv");
+    opts.optopt("", "format", "Output format. Only svg is supported for now.", "svg");
+    opts.optopt("", "title", "Override chart title.", "TEXT");
+    opts.optopt("", "width", "Override 'hor_axis.width'.", "PX");
+    opts.optopt("", "font", "Override 'items_font.family'.", "FAMILY");
+    opts.optopt("", "theme", "Override the theme.", "default|minimal|dark|print");
+    opts.optflag("", "debug", "Draw layout bounding boxes.");
+    opts.optflag("", "check", "Validate the config without rendering.");
+    opts.optflag("", "watch", "Re-render on config, data or font change.");
+    opts.optopt("", "batch", "Render all configs matching the pattern into out_dir.",
+                "GLOB");
+    opts.optflag("h", "help", "Print this help.");
+
+    let matches = match opts.parse(&args) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            print_usage(&opts);
+        }
+    };
+
+    if matches.opt_present("help") {
+        println!("{}", opts.usage(USAGE));
+        return;
+    }
+
+    let options = match parse_options(&matches) {
+        Ok(o) => o,
+        Err(msg) => {
+            eprintln!("Error: {}", msg);
+            process::exit(2);
+        }
+    };
+
     // detect system font
-    let system_font = main_try!(FontData::system_font());
+    let system_font = match FontData::system_font() {
+        Ok(f) => f,
+        Err(e) => {
+            print_error(&e);
+            process::exit(1);
+        }
+    };
+
+    let free = &matches.free;
+    let ok = match matches.opt_str("batch") {
+        Some(pattern) => {
+            if options.watch {
+                eprintln!("Error: --watch can't be used with --batch");
+                process::exit(2);
+            }
+
+            let out_dir = match (free.len(), options.check) {
+                (0, true) => None,
+                (1, false) => Some(Path::new(&free[0])),
+                _ => print_usage(&opts),
+            };
+
+            batch(&pattern, out_dir, &system_font, &options)
+        }
+        None => {
+            let out_path = match (free.len(), options.check) {
+                (1, true) => None,
+                (2, false) => Some(free[1].as_str()),
+                _ => print_usage(&opts),
+            };
+
+            if options.watch {
+                match out_path {
+                    Some(out_path) if free[0] != "-" => {
+                        watch(&free[0], out_path, &system_font, &options)
+                    }
+                    _ => {
+                        eprintln!("Error: --watch requires input and output files");
+                        process::exit(2);
+                    }
+                }
+            }
+
+            match process_file(&free[0], out_path, &system_font, &options, &mut Vec::new()) {
+                Ok(_) => true,
+                Err(e) => {
+                    print_error(&e);
+                    false
+                }
+            }
+        }
+    };
+
+    if !ok {
+        process::exit(1);
+    }
+}
+
+static USAGE: &str = "\
+Usage:
+    barh [options] config.json out.svg
+    barh [options] --check config.json
+    barh [options] --watch config.json out.svg
+    barh [options] --batch 'configs/*.json' out_dir
+    barh schema
+
+Use '-' to read the config from stdin or write SVG to stdout.";
+
+fn print_usage(opts: &getopts::Options) -> ! {
+    eprintln!("{}", opts.usage(USAGE));
+    process::exit(2);
+}
+
+fn parse_options(matches: &getopts::Matches) -> Result<Options, String> {
+    let input_format = match matches.opt_str("input-format") {
+        Some(name) => {
+            match input::Format::from_name(&name) {
+                Some(f) => Some(f),
+                None => return Err(format!("unknown input format '{}'", name)),
+            }
+        }
+        None => None,
+    };
+
+    let output_format = match matches.opt_str("format") {
+        Some(name) => {
+            match OutputFormat::from_name(&name) {
+                Some(f) => f,
+                None => return Err(format!("unknown output format '{}'", name)),
+            }
+        }
+        None => OutputFormat::Svg,
+    };
+
+    let width = match matches.opt_str("width") {
+        Some(w) => {
+            match w.parse() {
+                Ok(w) => Some(w),
+                Err(_) => return Err(format!("invalid width '{}'", w)),
+            }
+        }
+        None => None,
+    };
+
+    let theme = match matches.opt_str("theme") {
+        Some(name) => {
+            if !theme::NAMES.contains(&name.as_str()) {
+                return Err(format!("unknown theme '{}'", name));
+            }
+
+            Some(name)
+        }
+        None => None,
+    };
+
+    Ok(Options {
+        input_format,
+        output_format,
+        check: matches.opt_present("check"),
+        watch: matches.opt_present("watch"),
+        title: matches.opt_str("title"),
+        width,
+        font: matches.opt_str("font"),
+        theme,
+        debug: matches.opt_present("debug"),
+    })
+}
+
+fn print_error(e: &dyn StdError) {
+    let stderr = io::stderr();
+    let mut stderr = stderr.lock();
+
+    let _ = write!(stderr, "Error: {}", e);
+    let mut source = e.source();
+    while let Some(s) = source {
+        let _ = write!(stderr, ": {}", s);
+        source = s.source();
+    }
+    let _ = writeln!(stderr);
+}
+
+/// Processes all configs matching the pattern.
+///
+/// Returns `false` if any of them failed.
+fn batch(pattern: &str, out_dir: Option<&Path>, system_font: &FontData, opt: &Options) -> bool {
+    let paths = match glob::glob(pattern) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Error: invalid pattern '{}': {}", pattern, e);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    let mut count = 0;
+    for path in paths {
+        let path = match path {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ok = false;
+                continue;
+            }
+        };
+
+        let in_path = path.to_string_lossy();
+        let out_path = out_dir.map(|dir| {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            dir.join(format!("{}.{}", name, opt.output_format.extension()))
+        });
+        let out_path = out_path.as_ref().map(|p| p.to_string_lossy());
+        let out_path = out_path.as_ref().map(|p| p.as_ref());
+
+        count += 1;
+        if let Err(e) = process_file(&in_path, out_path, system_font, opt, &mut Vec::new()) {
+            eprint!("{}: ", in_path);
+            print_error(&e);
+            ok = false;
+        }
+    }
+
+    if count == 0 {
+        eprintln!("Error: no files match '{}'", pattern);
+        return false;
+    }
 
-    // load config file
-    let data = main_try!(load_file(&args[1]));
-    // parse json
-    let value: serde_json::Value = main_try!(serde_json::from_slice(&data));
+    ok
+}
+
+/// Loads the config and renders it into `out_path`.
+///
+/// Only validates the config when `out_path` is not set.
+///
+/// Files used during rendering are appended to `deps`, even on failure.
+fn process_file(in_path: &str, out_path: Option<&str>, system_font: &FontData, opt: &Options,
+                deps: &mut Vec<PathBuf>)
+    -> BarhResult<()>
+{
+    deps.push(PathBuf::from(in_path));
+
+    // load and parse config file
+    let in_format = opt.input_format.unwrap_or_else(|| input::Format::from_path(in_path));
+    let mut value = input::parse(&input::read(in_path)?, in_format)?;
+    // load referenced data
+    let base_dir = input::base_dir(in_path);
+    deps.extend(data::source_path(&value, base_dir));
+    data::resolve(&mut value, base_dir)?;
+    apply_overrides(&mut value, opt);
     // generate config from json
-    let conf = main_try!(Config::from_value(&value, &system_font));
+    let conf = Config::from_value(&value, system_font)?;
+    deps.push(PathBuf::from(&conf.items_font.path));
 
     // load font from config
-    let fm = main_try!(FontMetrics::from_font(&conf.items_font.family, conf.items_font.size));
+    let fm = FontMetrics::from_font(&conf.items_font.family, conf.items_font.size)?;
+
+    let out_path = match out_path {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+
+    let ouput_data = match opt.output_format {
+        OutputFormat::Svg => render_svg(&conf, &fm),
+    };
+
+    write_output(out_path, &ouput_data).map_err(|e| {
+        let name = if out_path == "-" { "stdout" } else { out_path };
+        Error::IoError(name.to_string(), e)
+    })
+}
+
+/// Writes data to a file or stdout when `path` is `-`.
+fn write_output(path: &str, data: &[u8]) -> io::Result<()> {
+    if path == "-" {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(data)?;
+        stdout.flush()
+    } else {
+        let mut file = File::create(path)?;
+        file.write_all(data)
+    }
+}
+
+/// Renders the config on every change of it or the files it depends on.
+#[cfg(target_os = "linux")]
+fn watch(in_path: &str, out_path: &str, system_font: &FontData, opt: &Options) -> ! {
+    use std::collections::HashSet;
+    use std::thread;
+    use std::time::Duration;
+
+    use inotify::{Inotify, WatchMask};
+
+    let mut inotify = match Inotify::init() {
+        Ok(i) => i,
+        Err(e) => {
+            print_error(&e);
+            process::exit(1);
+        }
+    };
+
+    // files are watched via their directories, since most editors replace files on save
+    let mut dirs = HashSet::new();
+    let mut buffer = [0; 4096];
+
+    loop {
+        let mut deps = Vec::new();
+        match process_file(in_path, Some(out_path), system_font, opt, &mut deps) {
+            Ok(_) => eprintln!("{}: updated", out_path),
+            Err(e) => print_error(&e),
+        }
+
+        // dependencies can change between passes, e.g. a new data file
+        let mut files = HashSet::new();
+        let mut new_dirs = HashSet::new();
+        for path in deps {
+            let dir = match path.parent() {
+                Some(d) if !d.as_os_str().is_empty() => d.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+
+            let name = match path.file_name() {
+                Some(n) => n.to_os_string(),
+                None => continue,
+            };
+
+            let mask = WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE;
+            match inotify.watches().add(&dir, mask) {
+                Ok(wd) => {
+                    new_dirs.insert(wd.clone());
+                    files.insert((wd, name));
+                }
+                Err(e) => eprintln!("Error: failed to watch '{}': {}", dir.display(), e),
+            }
+        }
+
+        for wd in dirs.difference(&new_dirs) {
+            // fails when a directory is already removed, which is fine
+            let _ = inotify.watches().remove(wd.clone());
+        }
+        dirs = new_dirs;
+
+        // wait for a change of any of the files
+        'wait: loop {
+            let events = match inotify.read_events_blocking(&mut buffer) {
+                Ok(events) => events,
+                Err(e) => {
+                    print_error(&e);
+                    process::exit(1);
+                }
+            };
+
+            for event in events {
+                if let Some(name) = event.name {
+                    if files.contains(&(event.wd.clone(), name.to_os_string())) {
+                        break 'wait;
+                    }
+                }
+            }
+        }
+
+        // skip the rest of the events produced by a single save
+        thread::sleep(Duration::from_millis(100));
+        while let Ok(events) = inotify.read_events(&mut buffer) {
+            if events.count() == 0 {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn watch(_: &str, _: &str, _: &FontData, _: &Options) -> ! {
+    eprintln!("Error: --watch is supported only on Linux");
+    process::exit(2);
+}
+
+fn apply_overrides(value: &mut Value, opt: &Options) {
+    let conf = match value.as_object_mut() {
+        Some(c) => c,
+        None => return, // will be reported by Config::from_value
+    };
+
+    fn sub_object<'a>(conf: &'a mut serde_json::Map<String, Value>, name: &str)
+        -> &'a mut serde_json::Map<String, Value>
+    {
+        let v = conf.entry(name).or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if !v.is_object() {
+            *v = Value::Object(serde_json::Map::new());
+        }
+        v.as_object_mut().unwrap()
+    }
+
+    if let Some(ref title) = opt.title {
+        conf.insert("title".to_string(), Value::from(title.as_str()));
+    }
+
+    if let Some(width) = opt.width {
+        sub_object(conf, "hor_axis").insert("width".to_string(), Value::from(width));
+    }
+
+    if let Some(ref font) = opt.font {
+        sub_object(conf, "items_font").insert("family".to_string(), Value::from(font.as_str()));
+    }
+
+    if let Some(ref theme) = opt.theme {
+        // keep inline overrides
+        match conf.get_mut("theme") {
+            Some(&mut Value::Object(ref mut obj)) => {
+                obj.insert("base".to_string(), Value::from(theme.as_str()));
+            }
+            _ => {
+                conf.insert("theme".to_string(), Value::from(theme.as_str()));
+            }
+        }
+    }
+
+    if opt.debug {
+        conf.insert("debug".to_string(), Value::Bool(true));
+    }
+}
 
+fn render_svg(conf: &Config, fm: &FontMetrics) -> Vec<u8> {
     // init layout
-    let mut lay = MainLayout::new(&conf);
+    let mut lay = MainLayout::new(conf);
     // set debug mode
     lay.set_enable_debug(conf.debug);
     // calculate layout
-    lay.calc_layout(&fm);
+    lay.calc_layout(fm);
 
     // init SVG DOM
     let mut doc = Document::new();
@@ -78,15 +517,13 @@ fn main() {
     svg.set_attribute((AId::ShapeRendering, "crispEdges"));
 
     // draw layout to SVG DOM
-    lay.draw_layout(&fm, 0, 0, &mut svg);
+    lay.draw_layout(fm, 0, 0, &mut svg);
 
     // set sizes
     svg.set_attribute((AId::Width, lay.width() as f64 + 1.0));
     svg.set_attribute((AId::Height, lay.height() as f64 + 1.0));
 
-    // write SVG to file
     let mut ouput_data = Vec::new();
     doc.write_buf(&mut ouput_data);
-    let mut out_file = main_try!(File::create(&args[2]));
-    main_try!(out_file.write_all(&ouput_data));
+    ouput_data
 }
@@ -0,0 +1,265 @@
+/// Number precision.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Precision {
+    /// Shortest representation.
+    Auto,
+    /// Number of digits after the decimal mark.
+    Decimals(u8),
+    /// Number of significant digits. Trailing zeros are removed.
+    Significant(u8),
+}
+
+/// A number formatting options used by tick labels and annotations.
+///
+/// The default value produces the same output as `f64::to_string`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumberFormat<'a> {
+    pub precision: Precision,
+    pub decimal_mark: &'a str,
+    /// Digits grouping is disabled when not set.
+    pub thousands_separator: Option<&'a str>,
+    /// Use k, M, G, T prefixes for large numbers.
+    pub si: bool,
+    /// Multiply by 100 and add the `%` sign.
+    pub percent: bool,
+    pub prefix: &'a str,
+    pub suffix: &'a str,
+}
+
+impl<'a> Default for NumberFormat<'a> {
+    fn default() -> NumberFormat<'a> {
+        NumberFormat {
+            precision: Precision::Auto,
+            decimal_mark: ".",
+            thousands_separator: None,
+            si: false,
+            percent: false,
+            prefix: "",
+            suffix: "",
+        }
+    }
+}
+
+/// Returns a decimal mark and a thousands separator used by the locale.
+pub fn locale_marks(locale: &str) -> Option<(&'static str, &'static str)> {
+    match locale {
+        "en" => Some((".", ",")),
+        "de" | "es" | "it" | "nl" | "pt" => Some((",", ".")),
+        "fr" => Some((",", "\u{202f}")),
+        "ru" | "pl" | "cs" | "sv" => Some((",", "\u{a0}")),
+        "ch" => Some((".", "'")),
+        _ => None,
+    }
+}
+
+static SI_PREFIXES: &[(f64, &str)] = &[
+    (1e12, "T"),
+    (1e9, "G"),
+    (1e6, "M"),
+    (1e3, "k"),
+];
+
+impl<'a> NumberFormat<'a> {
+    pub fn format(&self, value: f64) -> String {
+        let mut value = value;
+        if self.percent {
+            value *= 100.0;
+        }
+
+        let mut precision = self.precision;
+        let mut si_prefix = "";
+        if self.si {
+            if precision == Precision::Auto {
+                precision = Precision::Significant(3);
+            }
+
+            // round first, so 999.9 will become 1k and not 1000
+            if let Precision::Significant(digits) = precision {
+                value = round_significant(value, digits);
+            }
+
+            for &(factor, prefix) in SI_PREFIXES {
+                if value.abs() >= factor {
+                    value /= factor;
+                    si_prefix = prefix;
+                    break;
+                }
+            }
+        }
+
+        let mut text = match precision {
+            Precision::Auto => value.to_string(),
+            Precision::Decimals(n) => format!("{:.*}", n as usize, value),
+            Precision::Significant(n) => {
+                let value = round_significant(value, n);
+                let decimals = if value == 0.0 {
+                    0
+                } else {
+                    (n as i32 - 1 - value.abs().log10().floor() as i32).max(0)
+                };
+
+                let mut s = format!("{:.*}", decimals as usize, value);
+                if s.contains('.') {
+                    while s.ends_with('0') {
+                        s.pop();
+                    }
+
+                    if s.ends_with('.') {
+                        s.pop();
+                    }
+                }
+                s
+            }
+        };
+
+        if text == "-0" {
+            text = "0".to_string();
+        }
+
+        let (int_part, fract_part) = match text.find('.') {
+            Some(idx) => (&text[..idx], Some(&text[idx + 1..])),
+            None => (text.as_str(), None),
+        };
+
+        let mut s = String::new();
+        s.push_str(self.prefix);
+
+        match self.thousands_separator {
+            Some(sep) => {
+                let digits = int_part.trim_start_matches('-');
+                if digits.len() != int_part.len() {
+                    s.push('-');
+                }
+
+                for (i, c) in digits.chars().enumerate() {
+                    if i != 0 && (digits.len() - i) % 3 == 0 {
+                        s.push_str(sep);
+                    }
+                    s.push(c);
+                }
+            }
+            None => s.push_str(int_part),
+        }
+
+        if let Some(fract) = fract_part {
+            s.push_str(self.decimal_mark);
+            s.push_str(fract);
+        }
+
+        s.push_str(si_prefix);
+        if self.percent {
+            s.push('%');
+        }
+        s.push_str(self.suffix);
+
+        s
+    }
+}
+
+fn round_significant(value: f64, digits: u8) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+
+    let exp = digits as i32 - 1 - value.abs().log10().floor() as i32;
+    // dividing by an exact power of 10 is more precise than multiplying by its inverse
+    if exp >= 0 {
+        let k = 10f64.powi(exp);
+        (value * k).round() / k
+    } else {
+        let k = 10f64.powi(-exp);
+        (value / k).round() * k
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test {
+        ($name:ident, $value:expr, $fmt:expr, $result:expr) => (
+            #[test]
+            fn $name() {
+                assert_eq!($fmt.format($value), $result);
+            }
+        )
+    }
+
+    test!(default_1, 14.4, NumberFormat::default(), "14.4");
+    test!(default_2, 1200.0, NumberFormat::default(), "1200");
+    test!(default_3, 0.1 + 0.2, NumberFormat::default(), "0.30000000000000004");
+
+    test!(decimals_1, 55.50000001, NumberFormat {
+        precision: Precision::Decimals(1),
+        ..NumberFormat::default()
+    }, "55.5");
+
+    test!(decimals_2, 3.0, NumberFormat {
+        precision: Precision::Decimals(2),
+        ..NumberFormat::default()
+    }, "3.00");
+
+    test!(significant_1, 55.50000001, NumberFormat {
+        precision: Precision::Significant(3),
+        ..NumberFormat::default()
+    }, "55.5");
+
+    test!(significant_2, 12345.0, NumberFormat {
+        precision: Precision::Significant(2),
+        ..NumberFormat::default()
+    }, "12000");
+
+    test!(significant_3, 0.0012345, NumberFormat {
+        precision: Precision::Significant(2),
+        ..NumberFormat::default()
+    }, "0.0012");
+
+    test!(thousands_1, 1234567.5, NumberFormat {
+        thousands_separator: Some(","),
+        ..NumberFormat::default()
+    }, "1,234,567.5");
+
+    test!(thousands_2, -123456.0, NumberFormat {
+        thousands_separator: Some(" "),
+        ..NumberFormat::default()
+    }, "-123 456");
+
+    test!(locale_1, 1234.5, NumberFormat {
+        decimal_mark: ",",
+        thousands_separator: Some("."),
+        ..NumberFormat::default()
+    }, "1.234,5");
+
+    test!(si_1, 1200.0, NumberFormat {
+        si: true,
+        suffix: " ms",
+        ..NumberFormat::default()
+    }, "1.2k ms");
+
+    test!(si_2, 999.96, NumberFormat {
+        si: true,
+        ..NumberFormat::default()
+    }, "1k");
+
+    test!(si_3, 2500000.0, NumberFormat {
+        si: true,
+        precision: Precision::Decimals(2),
+        ..NumberFormat::default()
+    }, "2.50M");
+
+    test!(si_4, 500.0, NumberFormat {
+        si: true,
+        ..NumberFormat::default()
+    }, "500");
+
+    test!(percent_1, 0.255, NumberFormat {
+        percent: true,
+        precision: Precision::Decimals(1),
+        ..NumberFormat::default()
+    }, "25.5%");
+
+    test!(prefix_1, 20.0, NumberFormat {
+        prefix: "$",
+        ..NumberFormat::default()
+    }, "$20");
+}
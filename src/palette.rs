@@ -0,0 +1,113 @@
+//! Categorical bar colors.
+
+use color::{self, Color};
+
+/// Names of the built-in palettes.
+pub const NAMES: &[&str] = &["google", "tableau10", "okabe_ito", "tol_bright"];
+
+static GOOGLE: &[&str] = &[
+    "#3366cc", "#dc3912", "#ff9900", "#109618", "#990099",
+    "#0099c6", "#dd4477", "#66aa00", "#b82e2e", "#316395",
+];
+
+static TABLEAU10: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f",
+    "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+/// Color-blind safe. https://jfly.uni-koeln.de/color/
+static OKABE_ITO: &[&str] = &[
+    "#e69f00", "#56b4e9", "#009e73", "#f0e442", "#0072b2", "#d55e00", "#cc79a7", "#000000",
+];
+
+/// Color-blind safe. https://personal.sron.nl/~pault/
+static TOL_BRIGHT: &[&str] = &[
+    "#4477aa", "#ee6677", "#228833", "#ccbb44", "#66ccee", "#aa3377", "#bbbbbb",
+];
+
+/// How an item color is selected from a palette.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorBy {
+    /// By the item position in the config.
+    Index,
+    /// By the item name hash. The same name always has the same color.
+    Name,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Palette {
+    colors: Vec<Color>,
+    color_by: ColorBy,
+}
+
+impl Palette {
+    /// Creates a palette.
+    ///
+    /// `colors` must not be empty.
+    pub fn new(colors: Vec<Color>, color_by: ColorBy) -> Palette {
+        debug_assert!(!colors.is_empty());
+        Palette { colors, color_by }
+    }
+
+    /// Returns a built-in palette.
+    pub fn by_name(name: &str, color_by: ColorBy) -> Option<Palette> {
+        let colors = match name {
+            "google" => GOOGLE,
+            "tableau10" => TABLEAU10,
+            "okabe_ito" => OKABE_ITO,
+            "tol_bright" => TOL_BRIGHT,
+            _ => return None,
+        };
+
+        let colors = colors.iter().map(|c| color::parse(c).unwrap()).collect();
+        Some(Palette::new(colors, color_by))
+    }
+
+    /// Returns a color of an item.
+    pub fn color(&self, index: usize, name: &str) -> Color {
+        let idx = match self.color_by {
+            ColorBy::Index => index,
+            ColorBy::Name => fnv1a(name) as usize,
+        };
+
+        self.colors[idx % self.colors.len()]
+    }
+}
+
+/// FNV-1a hash.
+///
+/// Unlike `DefaultHasher`, it's guaranteed to be the same in all builds.
+fn fnv1a(text: &str) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for b in text.bytes() {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_index_1() {
+        let p = Palette::by_name("google", ColorBy::Index).unwrap();
+        assert_eq!(p.color(0, "a"), Color::new(0x33, 0x66, 0xcc));
+        assert_eq!(p.color(1, "a"), Color::new(0xdc, 0x39, 0x12));
+        assert_eq!(p.color(10, "a"), Color::new(0x33, 0x66, 0xcc));
+        assert!(Palette::by_name("rainbow", ColorBy::Index).is_none());
+    }
+
+    #[test]
+    fn by_name_1() {
+        assert_eq!(fnv1a(""), 0x811c9dc5);
+        assert_eq!(fnv1a("a"), 0xe40c292c);
+
+        let colors = vec![Color::new(0, 0, 0), Color::new(1, 1, 1), Color::new(2, 2, 2)];
+        let p = Palette::new(colors, ColorBy::Name);
+        assert_eq!(p.color(0, "serde"), p.color(5, "serde"));
+        assert_eq!(p.color(0, "a"), Color::new(1, 1, 1));
+    }
+}
@@ -0,0 +1,1130 @@
+use serde_json::{Map, Value};
+
+use config::Error;
+use theme;
+use palette;
+
+/// A value type of the config field.
+pub enum Type {
+    String,
+    Number,
+    UInt,
+    StringOrUInt,
+    NumberOrString,
+    Bool,
+    /// A string with one of the listed values.
+    Enum(&'static [&'static str]),
+    Array(&'static Field),
+    Object(&'static [Field]),
+    /// A string with one of the listed values or an object.
+    EnumOrObject(&'static [&'static str], &'static [Field]),
+    /// A string with one of the listed values or an array.
+    EnumOrArray(&'static [&'static str], &'static Field),
+}
+
+impl Type {
+    fn name(&self) -> &'static str {
+        match *self {
+            Type::String => "String",
+            Type::Number => "Number",
+            Type::UInt => "UInt",
+            Type::StringOrUInt => "String or UInt",
+            Type::NumberOrString => "Number or String",
+            Type::Bool => "Bool",
+            Type::Enum(_) => "String",
+            Type::Array(_) => "Array",
+            Type::Object(_) => "Object",
+            Type::EnumOrObject(..) => "String or Object",
+            Type::EnumOrArray(..) => "String or Array",
+        }
+    }
+
+    fn is_valid(&self, value: &Value) -> bool {
+        match *self {
+            Type::String => value.is_string(),
+            Type::Number => value.is_number(),
+            Type::UInt => value.is_u64(),
+            Type::StringOrUInt => value.is_string() || value.is_u64(),
+            Type::NumberOrString => value.is_number() || value.is_string(),
+            Type::Bool => value.is_boolean(),
+            Type::Enum(_) => value.is_string(),
+            Type::Array(_) => value.is_array(),
+            Type::Object(_) => value.is_object(),
+            Type::EnumOrObject(..) => value.is_string() || value.is_object(),
+            Type::EnumOrArray(..) => value.is_string() || value.is_array(),
+        }
+    }
+}
+
+/// A config field definition.
+///
+/// Array elements are described by a field named `n`, which is also used
+/// instead of indexes in paths.
+pub struct Field {
+    pub name: &'static str,
+    pub path: &'static str,
+    pub kind: Type,
+    pub required: bool,
+    pub description: &'static str,
+}
+
+pub static CONFIG_FIELDS: &[Field] = &[
+    Field {
+        name: "title",
+        path: "/title",
+        kind: Type::String,
+        required: false,
+        description: "Chart title.",
+    },
+    Field {
+        name: "items",
+        path: "/items",
+        kind: Type::Array(&ITEM),
+        required: false,
+        description: "A list of bars. Must not be empty unless `data` is set.",
+    },
+    Field {
+        name: "data",
+        path: "/data",
+        kind: Type::Object(DATA_FIELDS),
+        required: false,
+        description: "Items loaded from a CSV file. Appended to `items`.",
+    },
+    Field {
+        name: "items_font",
+        path: "/items_font",
+        kind: Type::Object(ITEMS_FONT_FIELDS),
+        required: false,
+        description: "A font used for all text. System font by default.",
+    },
+    Field {
+        name: "hor_axis",
+        path: "/hor_axis",
+        kind: Type::Object(HOR_AXIS_FIELDS),
+        required: false,
+        description: "Horizontal axis options.",
+    },
+    Field {
+        name: "number_format",
+        path: "/number_format",
+        kind: Type::Object(NUMBER_FORMAT_FIELDS),
+        required: false,
+        description: "Number format of tick labels and annotations.",
+    },
+    Field {
+        name: "groups",
+        path: "/groups",
+        kind: Type::Array(&GROUP),
+        required: false,
+        description: "Named groups of items drawn after `items` under a header.",
+    },
+    Field {
+        name: "group_separator",
+        path: "/group_separator",
+        kind: Type::Bool,
+        required: false,
+        description: "Draw a line between groups.",
+    },
+    Field {
+        name: "sort",
+        path: "/sort",
+        kind: Type::Enum(&["none", "value_desc", "value_asc", "name"]),
+        required: false,
+        description: "Items order. Default: none, which keeps the config order.",
+    },
+    Field {
+        name: "limit",
+        path: "/limit",
+        kind: Type::UInt,
+        required: false,
+        description: "Maximum number of items in the chart or in each group. \
+                      Applied after sorting.",
+    },
+    Field {
+        name: "other",
+        path: "/other",
+        kind: Type::String,
+        required: false,
+        description: "Collapse items beyond `limit` into a bar with this name, \
+                      one per group.",
+    },
+    Field {
+        name: "reference_lines",
+        path: "/reference_lines",
+        kind: Type::Array(&REFERENCE_LINE),
+        required: false,
+        description: "Vertical lines across the bars, like a baseline.",
+    },
+    Field {
+        name: "bands",
+        path: "/bands",
+        kind: Type::Array(&BAND),
+        required: false,
+        description: "Shaded value ranges behind the bars.",
+    },
+    Field {
+        name: "theme",
+        path: "/theme",
+        kind: Type::EnumOrObject(theme::NAMES, THEME_FIELDS),
+        required: false,
+        description: "A built-in theme name or an object with theme fields.",
+    },
+    Field {
+        name: "annotation",
+        path: "/annotation",
+        kind: Type::EnumOrObject(ANNOTATION_VARIANTS, ANNOTATION_FIELDS),
+        required: false,
+        description: "Bar value labels. `show`, `hide` or an object with options.",
+    },
+    Field {
+        name: "palette",
+        path: "/palette",
+        kind: Type::EnumOrArray(palette::NAMES, &PALETTE_COLOR),
+        required: false,
+        description: "A built-in palette name or a list of colors for items without `color`.",
+    },
+    Field {
+        name: "color_by",
+        path: "/color_by",
+        kind: Type::Enum(&["index", "name"]),
+        required: false,
+        description: "Select palette colors by item position or by item name hash.",
+    },
+    Field {
+        name: "orientation",
+        path: "/orientation",
+        kind: Type::Enum(&["horizontal", "vertical"]),
+        required: false,
+        description: "Horizontal bars or vertical columns.",
+    },
+    Field {
+        name: "background",
+        path: "/background",
+        kind: Type::String,
+        required: false,
+        description: "Chart background color. Overrides the theme one.",
+    },
+    Field {
+        name: "padding",
+        path: "/padding",
+        kind: Type::UInt,
+        required: false,
+        description: "A space around the chart in pixels.",
+    },
+    Field {
+        name: "plot_area",
+        path: "/plot_area",
+        kind: Type::Object(PLOT_AREA_FIELDS),
+        required: false,
+        description: "A style of the region with bars.",
+    },
+    Field {
+        name: "accessibility",
+        path: "/accessibility",
+        kind: Type::Object(ACCESSIBILITY_FIELDS),
+        required: false,
+        description: "Texts for screen readers.",
+    },
+    Field {
+        name: "value_type",
+        path: "/value_type",
+        kind: Type::Enum(&["number", "duration", "date"]),
+        required: false,
+        description: "A type of item values. Default: number.",
+    },
+    Field {
+        name: "value_unit",
+        path: "/value_unit",
+        kind: Type::Enum(&["ns", "us", "ms", "s", "min", "h", "d"]),
+        required: false,
+        description: "A unit of numeric durations and timestamps. \
+                      Also used by the axis options. Default: s.",
+    },
+    Field {
+        name: "debug",
+        path: "/debug",
+        kind: Type::Bool,
+        required: false,
+        description: "Draw layout bounding boxes.",
+    },
+];
+
+static ITEM: Field = Field {
+    name: "n",
+    path: "/items/n",
+    kind: Type::Object(ITEM_FIELDS),
+    required: false,
+    description: "A single bar.",
+};
+
+static ITEM_FIELDS: &[Field] = &[
+    Field {
+        name: "name",
+        path: "/items/n/name",
+        kind: Type::String,
+        required: true,
+        description: "Bar label.",
+    },
+    Field {
+        name: "value",
+        path: "/items/n/value",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "Bar value. Must be positive. Required unless `start` and `end` are set. \
+                      Durations and dates can be set as strings, like `1.5s` or `2018-03-25`.",
+    },
+    Field {
+        name: "start",
+        path: "/items/n/start",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "Start of a range bar. Used with `end` instead of `value`.",
+    },
+    Field {
+        name: "end",
+        path: "/items/n/end",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "End of a range bar.",
+    },
+    Field {
+        name: "error",
+        path: "/items/n/error",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "Symmetric error margin drawn as a whisker.",
+    },
+    Field {
+        name: "error_low",
+        path: "/items/n/error_low",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "Lower error margin. Overrides `error`.",
+    },
+    Field {
+        name: "error_high",
+        path: "/items/n/error_high",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "Upper error margin. Overrides `error`.",
+    },
+    Field {
+        name: "target",
+        path: "/items/n/target",
+        kind: Type::NumberOrString,
+        required: false,
+        description: "A target value drawn as a marker over the bar.",
+    },
+    Field {
+        name: "color",
+        path: "/items/n/color",
+        kind: Type::String,
+        required: false,
+        description: "Bar fill color.",
+    },
+    Field {
+        name: "annotation",
+        path: "/items/n/annotation",
+        kind: Type::EnumOrObject(ANNOTATION_VARIANTS, ITEM_ANNOTATION_FIELDS),
+        required: false,
+        description: "Overrides the global `annotation` for this bar.",
+    },
+];
+
+static ANNOTATION_VARIANTS: &[&str] = &["show", "hide"];
+static PLACEMENTS: &[&str] = &["auto", "inside_end", "inside_start", "outside", "center"];
+
+static ANNOTATION_FIELDS: &[Field] = &[
+    Field {
+        name: "show",
+        path: "/annotation/show",
+        kind: Type::Bool,
+        required: false,
+        description: "Draw annotations. Default: true.",
+    },
+    Field {
+        name: "placement",
+        path: "/annotation/placement",
+        kind: Type::Enum(PLACEMENTS),
+        required: false,
+        description: "Annotation position relative to the bar. Default: auto.",
+    },
+    Field {
+        name: "template",
+        path: "/annotation/template",
+        kind: Type::String,
+        required: false,
+        description: "Annotation text with `{name}`, `{value}`, `{suffix}` \
+                      and `{percent}` placeholders.",
+    },
+];
+
+static ITEM_ANNOTATION_FIELDS: &[Field] = &[
+    Field {
+        name: "show",
+        path: "/items/n/annotation/show",
+        kind: Type::Bool,
+        required: false,
+        description: "Draw the annotation.",
+    },
+    Field {
+        name: "placement",
+        path: "/items/n/annotation/placement",
+        kind: Type::Enum(PLACEMENTS),
+        required: false,
+        description: "Annotation position relative to the bar.",
+    },
+    Field {
+        name: "template",
+        path: "/items/n/annotation/template",
+        kind: Type::String,
+        required: false,
+        description: "Annotation text. Can be a free text.",
+    },
+];
+
+static THEME_FIELDS: &[Field] = &[
+    Field {
+        name: "base",
+        path: "/theme/base",
+        kind: Type::Enum(theme::NAMES),
+        required: false,
+        description: "A built-in theme to override. Default: default.",
+    },
+    Field {
+        name: "background",
+        path: "/theme/background",
+        kind: Type::String,
+        required: false,
+        description: "Background color. `none` for a transparent background.",
+    },
+    Field {
+        name: "text_color",
+        path: "/theme/text_color",
+        kind: Type::String,
+        required: false,
+        description: "Title, item names and axis title color.",
+    },
+    Field {
+        name: "title_font_weight",
+        path: "/theme/title_font_weight",
+        kind: Type::String,
+        required: false,
+        description: "Chart title font weight.",
+    },
+    Field {
+        name: "group_header_font_weight",
+        path: "/theme/group_header_font_weight",
+        kind: Type::String,
+        required: false,
+        description: "Group headers font weight.",
+    },
+    Field {
+        name: "axis_title_font_style",
+        path: "/theme/axis_title_font_style",
+        kind: Type::String,
+        required: false,
+        description: "Axis title font style.",
+    },
+    Field {
+        name: "bar_color",
+        path: "/theme/bar_color",
+        kind: Type::String,
+        required: false,
+        description: "Default bar color.",
+    },
+    Field {
+        name: "other_bar_color",
+        path: "/theme/other_bar_color",
+        kind: Type::String,
+        required: false,
+        description: "Color of the bar with collapsed items.",
+    },
+    Field {
+        name: "axis_color",
+        path: "/theme/axis_color",
+        kind: Type::String,
+        required: false,
+        description: "Color of the first tick.",
+    },
+    Field {
+        name: "tick_color",
+        path: "/theme/tick_color",
+        kind: Type::String,
+        required: false,
+        description: "Gridlines color.",
+    },
+    Field {
+        name: "minor_tick_color",
+        path: "/theme/minor_tick_color",
+        kind: Type::String,
+        required: false,
+        description: "Minor gridlines color.",
+    },
+    Field {
+        name: "tick_text_color",
+        path: "/theme/tick_text_color",
+        kind: Type::String,
+        required: false,
+        description: "Tick labels color.",
+    },
+    Field {
+        name: "annotation_text_color",
+        path: "/theme/annotation_text_color",
+        kind: Type::String,
+        required: false,
+        description: "Color of annotations inside bars.",
+    },
+    Field {
+        name: "annotation_text_color_alt",
+        path: "/theme/annotation_text_color_alt",
+        kind: Type::String,
+        required: false,
+        description: "Color of annotations outside bars.",
+    },
+    Field {
+        name: "annotation_handle_color",
+        path: "/theme/annotation_handle_color",
+        kind: Type::String,
+        required: false,
+        description: "Color of a line to an outside annotation.",
+    },
+    Field {
+        name: "break_marker_color",
+        path: "/theme/break_marker_color",
+        kind: Type::String,
+        required: false,
+        description: "Axis break marker color.",
+    },
+    Field {
+        name: "break_marker_width",
+        path: "/theme/break_marker_width",
+        kind: Type::UInt,
+        required: false,
+        description: "Axis break marker width.",
+    },
+    Field {
+        name: "whisker_color",
+        path: "/theme/whisker_color",
+        kind: Type::String,
+        required: false,
+        description: "Error whiskers color.",
+    },
+    Field {
+        name: "whisker_width",
+        path: "/theme/whisker_width",
+        kind: Type::UInt,
+        required: false,
+        description: "Error whiskers width.",
+    },
+    Field {
+        name: "target_color",
+        path: "/theme/target_color",
+        kind: Type::String,
+        required: false,
+        description: "Target markers color.",
+    },
+    Field {
+        name: "reference_line_color",
+        path: "/theme/reference_line_color",
+        kind: Type::String,
+        required: false,
+        description: "Default reference lines color.",
+    },
+    Field {
+        name: "reference_line_width",
+        path: "/theme/reference_line_width",
+        kind: Type::UInt,
+        required: false,
+        description: "Reference lines width.",
+    },
+    Field {
+        name: "band_color",
+        path: "/theme/band_color",
+        kind: Type::String,
+        required: false,
+        description: "Default bands color.",
+    },
+    Field {
+        name: "band_text_color",
+        path: "/theme/band_text_color",
+        kind: Type::String,
+        required: false,
+        description: "Band labels color.",
+    },
+    Field {
+        name: "group_separator_color",
+        path: "/theme/group_separator_color",
+        kind: Type::String,
+        required: false,
+        description: "Group separators color.",
+    },
+];
+
+static GROUP: Field = Field {
+    name: "n",
+    path: "/groups/n",
+    kind: Type::Object(GROUP_FIELDS),
+    required: false,
+    description: "A single group.",
+};
+
+static GROUP_FIELDS: &[Field] = &[
+    Field {
+        name: "name",
+        path: "/groups/n/name",
+        kind: Type::String,
+        required: true,
+        description: "Group header.",
+    },
+    Field {
+        name: "items",
+        path: "/groups/n/items",
+        kind: Type::Array(&GROUP_ITEM),
+        required: true,
+        description: "Group bars.",
+    },
+];
+
+static GROUP_ITEM: Field = Field {
+    name: "n",
+    path: "/groups/n/items/n",
+    kind: Type::Object(ITEM_FIELDS),
+    required: false,
+    description: "A single bar.",
+};
+
+static REFERENCE_LINE: Field = Field {
+    name: "n",
+    path: "/reference_lines/n",
+    kind: Type::Object(REFERENCE_LINE_FIELDS),
+    required: false,
+    description: "A single reference line.",
+};
+
+static REFERENCE_LINE_FIELDS: &[Field] = &[
+    Field {
+        name: "value",
+        path: "/reference_lines/n/value",
+        kind: Type::NumberOrString,
+        required: true,
+        description: "Line position.",
+    },
+    Field {
+        name: "label",
+        path: "/reference_lines/n/label",
+        kind: Type::String,
+        required: false,
+        description: "Text drawn above the line.",
+    },
+    Field {
+        name: "color",
+        path: "/reference_lines/n/color",
+        kind: Type::String,
+        required: false,
+        description: "Line color.",
+    },
+];
+
+static BAND: Field = Field {
+    name: "n",
+    path: "/bands/n",
+    kind: Type::Object(BAND_FIELDS),
+    required: false,
+    description: "A single band.",
+};
+
+static BAND_FIELDS: &[Field] = &[
+    Field {
+        name: "from",
+        path: "/bands/n/from",
+        kind: Type::NumberOrString,
+        required: true,
+        description: "Band start.",
+    },
+    Field {
+        name: "to",
+        path: "/bands/n/to",
+        kind: Type::NumberOrString,
+        required: true,
+        description: "Band end.",
+    },
+    Field {
+        name: "label",
+        path: "/bands/n/label",
+        kind: Type::String,
+        required: false,
+        description: "Text drawn above the band.",
+    },
+    Field {
+        name: "color",
+        path: "/bands/n/color",
+        kind: Type::String,
+        required: false,
+        description: "Band fill color.",
+    },
+];
+
+pub static DATA_FIELDS: &[Field] = &[
+    Field {
+        name: "csv",
+        path: "/data/csv",
+        kind: Type::String,
+        required: true,
+        description: "Path to a CSV or TSV file. Relative to the config file.",
+    },
+    Field {
+        name: "delimiter",
+        path: "/data/delimiter",
+        kind: Type::String,
+        required: false,
+        description: "Field delimiter. Tab for *.tsv files and comma otherwise.",
+    },
+    Field {
+        name: "has_header",
+        path: "/data/has_header",
+        kind: Type::Bool,
+        required: false,
+        description: "Whether the first row is a header. Detected automatically by default.",
+    },
+    Field {
+        name: "name_column",
+        path: "/data/name_column",
+        kind: Type::StringOrUInt,
+        required: false,
+        description: "Column name or index with item names. The first column by default.",
+    },
+    Field {
+        name: "value_columns",
+        path: "/data/value_columns",
+        kind: Type::Array(&VALUE_COLUMN),
+        required: false,
+        description: "Column names or indexes with item values. \
+                      Only the first one is used for now. The second column by default.",
+    },
+];
+
+static VALUE_COLUMN: Field = Field {
+    name: "n",
+    path: "/data/value_columns/n",
+    kind: Type::StringOrUInt,
+    required: false,
+    description: "Column name or index.",
+};
+
+static ITEMS_FONT_FIELDS: &[Field] = &[
+    Field {
+        name: "family",
+        path: "/items_font/family",
+        kind: Type::String,
+        required: false,
+        description: "Font family name as known to fontconfig.",
+    },
+    Field {
+        name: "size",
+        path: "/items_font/size",
+        kind: Type::Number,
+        required: false,
+        description: "Font size in points.",
+    },
+];
+
+static PALETTE_COLOR: Field = Field {
+    name: "n",
+    path: "/palette/n",
+    kind: Type::String,
+    required: false,
+    description: "A color.",
+};
+
+static PLOT_AREA_FIELDS: &[Field] = &[
+    Field {
+        name: "background",
+        path: "/plot_area/background",
+        kind: Type::String,
+        required: false,
+        description: "Background color.",
+    },
+    Field {
+        name: "border",
+        path: "/plot_area/border",
+        kind: Type::String,
+        required: false,
+        description: "Border color. No border by default.",
+    },
+    Field {
+        name: "border_width",
+        path: "/plot_area/border_width",
+        kind: Type::UInt,
+        required: false,
+        description: "Border width in pixels. Default: 1.",
+    },
+    Field {
+        name: "radius",
+        path: "/plot_area/radius",
+        kind: Type::UInt,
+        required: false,
+        description: "Corner radius in pixels.",
+    },
+];
+
+static ACCESSIBILITY_FIELDS: &[Field] = &[
+    Field {
+        name: "summary",
+        path: "/accessibility/summary",
+        kind: Type::String,
+        required: false,
+        description: "A chart description. Generated from items by default.",
+    },
+    Field {
+        name: "data_table",
+        path: "/accessibility/data_table",
+        kind: Type::Bool,
+        required: false,
+        description: "Add a hidden description with all items as CSV.",
+    },
+];
+
+static HOR_AXIS_FIELDS: &[Field] = &[
+    Field {
+        name: "title",
+        path: "/hor_axis/title",
+        kind: Type::String,
+        required: false,
+        description: "Axis title drawn under the ticks.",
+    },
+    Field {
+        name: "suffix",
+        path: "/hor_axis/suffix",
+        kind: Type::String,
+        required: false,
+        description: "Text appended to the tick labels.",
+    },
+    Field {
+        name: "min_value",
+        path: "/hor_axis/min_value",
+        kind: Type::Number,
+        required: false,
+        description: "Axis minimum. Default: 0.",
+    },
+    Field {
+        name: "max_value",
+        path: "/hor_axis/max_value",
+        kind: Type::Number,
+        required: false,
+        description: "Axis maximum. Calculated from items by default. \
+                      Longer bars are clipped.",
+    },
+    Field {
+        name: "ticks",
+        path: "/hor_axis/ticks",
+        kind: Type::Array(&TICK),
+        required: false,
+        description: "Explicit list of tick values.",
+    },
+    Field {
+        name: "width",
+        path: "/hor_axis/width",
+        kind: Type::UInt,
+        required: false,
+        description: "Bars area width in pixels.",
+    },
+    Field {
+        name: "round_tick_values",
+        path: "/hor_axis/round_tick_values",
+        kind: Type::Bool,
+        required: false,
+        description: "Round tick labels to integers.",
+    },
+    Field {
+        name: "tick_count",
+        path: "/hor_axis/tick_count",
+        kind: Type::UInt,
+        required: false,
+        description: "Preferred number of ticks. At least 2. Default: 5.",
+    },
+    Field {
+        name: "tick_step",
+        path: "/hor_axis/tick_step",
+        kind: Type::Number,
+        required: false,
+        description: "Distance between ticks. Chosen automatically by default.",
+    },
+    Field {
+        name: "scale",
+        path: "/hor_axis/scale",
+        kind: Type::Enum(&["linear", "log"]),
+        required: false,
+        description: "Axis scale. Items must be greater than zero on a logarithmic scale.",
+    },
+    Field {
+        name: "log_base",
+        path: "/hor_axis/log_base",
+        kind: Type::Number,
+        required: false,
+        description: "Logarithm base. Default: 10.",
+    },
+    Field {
+        name: "axis_break",
+        path: "/hor_axis/axis_break",
+        kind: Type::Bool,
+        required: false,
+        description: "Draw an outlier bar truncated with a break marker.",
+    },
+];
+
+static NUMBER_FORMAT_FIELDS: &[Field] = &[
+    Field {
+        name: "decimals",
+        path: "/number_format/decimals",
+        kind: Type::UInt,
+        required: false,
+        description: "Fixed number of digits after the decimal mark.",
+    },
+    Field {
+        name: "significant",
+        path: "/number_format/significant",
+        kind: Type::UInt,
+        required: false,
+        description: "Number of significant digits. Can't be used with `decimals`.",
+    },
+    Field {
+        name: "grouping",
+        path: "/number_format/grouping",
+        kind: Type::Bool,
+        required: false,
+        description: "Separate thousands.",
+    },
+    Field {
+        name: "thousands_separator",
+        path: "/number_format/thousands_separator",
+        kind: Type::String,
+        required: false,
+        description: "Thousands separator. Enables grouping. Depends on `locale` by default.",
+    },
+    Field {
+        name: "decimal_mark",
+        path: "/number_format/decimal_mark",
+        kind: Type::String,
+        required: false,
+        description: "Decimal mark. Depends on `locale` by default.",
+    },
+    Field {
+        name: "locale",
+        path: "/number_format/locale",
+        kind: Type::String,
+        required: false,
+        description: "A language code, like `en` or `de`, used to select the decimal mark \
+                      and the thousands separator.",
+    },
+    Field {
+        name: "si",
+        path: "/number_format/si",
+        kind: Type::Bool,
+        required: false,
+        description: "Use k, M, G and T prefixes for large numbers.",
+    },
+    Field {
+        name: "percent",
+        path: "/number_format/percent",
+        kind: Type::Bool,
+        required: false,
+        description: "Multiply by 100 and add the % sign.",
+    },
+    Field {
+        name: "prefix",
+        path: "/number_format/prefix",
+        kind: Type::String,
+        required: false,
+        description: "Text inserted before numbers.",
+    },
+    Field {
+        name: "suffix",
+        path: "/number_format/suffix",
+        kind: Type::String,
+        required: false,
+        description: "Text appended to numbers.",
+    },
+];
+
+static TICK: Field = Field {
+    name: "n",
+    path: "/hor_axis/ticks/n",
+    kind: Type::Number,
+    required: false,
+    description: "Tick value.",
+};
+
+/// Checks that all known fields of the object have valid types.
+///
+/// Required fields and value ranges are checked by the `config` parser.
+pub fn validate(obj: &Map<String, Value>, fields: &[Field]) -> Result<(), Error> {
+    for field in fields {
+        if let Some(v) = obj.get(field.name) {
+            validate_field(v, field, field.path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks fields of a nested object, which can be an array element.
+fn validate_object(obj: &Map<String, Value>, fields: &[Field], path: &str) -> Result<(), Error> {
+    for field in fields {
+        if let Some(v) = obj.get(field.name) {
+            validate_field(v, field, &format!("{}/{}", path, field.name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a value at `path`, which has real array indexes instead of `n`.
+fn validate_field(value: &Value, field: &Field, path: &str) -> Result<(), Error> {
+    if !field.kind.is_valid(value) {
+        return Err(Error::InvalidValueType(path.to_string(), field.kind.name()));
+    }
+
+    match field.kind {
+        Type::Enum(variants) if !variants.contains(&value.as_str().unwrap()) => {
+            return Err(Error::InvalidVariant(path.to_string(), variants));
+        }
+        Type::Array(elem) => {
+            for (i, v) in value.as_array().unwrap().iter().enumerate() {
+                validate_field(v, elem, &format!("{}/{}", path, i))?;
+            }
+        }
+        Type::Object(fields) => validate_object(value.as_object().unwrap(), fields, path)?,
+        Type::EnumOrObject(variants, fields) => {
+            match *value {
+                Value::String(ref s) if !variants.contains(&s.as_str()) => {
+                    return Err(Error::InvalidVariant(path.to_string(), variants));
+                }
+                Value::Object(ref obj) => validate_object(obj, fields, path)?,
+                _ => {}
+            }
+        }
+        Type::EnumOrArray(variants, elem) => {
+            match *value {
+                Value::String(ref s) if !variants.contains(&s.as_str()) => {
+                    return Err(Error::InvalidVariant(path.to_string(), variants));
+                }
+                Value::Array(ref list) => {
+                    for (i, v) in list.iter().enumerate() {
+                        validate_field(v, elem, &format!("{}/{}", path, i))?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Returns a JSON Schema of the config format.
+pub fn json_schema() -> Value {
+    let mut root = Map::new();
+    root.insert("$schema".to_string(), "http://json-schema.org/draft-07/schema#".into());
+    root.insert("title".to_string(), "barh config".into());
+    gen_type(&Type::Object(CONFIG_FIELDS), &mut root);
+    Value::Object(root)
+}
+
+fn gen_field(field: &Field) -> Value {
+    let mut obj = Map::new();
+    obj.insert("description".to_string(), field.description.into());
+    gen_type(&field.kind, &mut obj);
+    Value::Object(obj)
+}
+
+fn gen_type(kind: &Type, obj: &mut Map<String, Value>) {
+    match *kind {
+        Type::String => {
+            obj.insert("type".to_string(), "string".into());
+        }
+        Type::Number => {
+            obj.insert("type".to_string(), "number".into());
+        }
+        Type::UInt => {
+            obj.insert("type".to_string(), "integer".into());
+            obj.insert("minimum".to_string(), 0.into());
+        }
+        Type::StringOrUInt => {
+            obj.insert("type".to_string(), Value::Array(vec!["string".into(), "integer".into()]));
+            obj.insert("minimum".to_string(), 0.into());
+        }
+        Type::NumberOrString => {
+            obj.insert("type".to_string(), Value::Array(vec!["number".into(), "string".into()]));
+        }
+        Type::Bool => {
+            obj.insert("type".to_string(), "boolean".into());
+        }
+        Type::Enum(variants) => {
+            obj.insert("type".to_string(), "string".into());
+            obj.insert("enum".to_string(), variants.iter().map(|v| Value::from(*v)).collect());
+        }
+        Type::Array(elem) => {
+            obj.insert("type".to_string(), "array".into());
+            obj.insert("items".to_string(), gen_field(elem));
+        }
+        Type::Object(fields) => {
+            obj.insert("type".to_string(), "object".into());
+
+            let mut props = Map::new();
+            for field in fields {
+                props.insert(field.name.to_string(), gen_field(field));
+            }
+            obj.insert("properties".to_string(), Value::Object(props));
+
+            let required: Vec<Value> = fields.iter()
+                .filter(|f| f.required)
+                .map(|f| f.name.into())
+                .collect();
+            if !required.is_empty() {
+                obj.insert("required".to_string(), Value::Array(required));
+            }
+        }
+        Type::EnumOrObject(variants, fields) => {
+            let mut name = Map::new();
+            gen_type(&Type::Enum(variants), &mut name);
+
+            let mut object = Map::new();
+            gen_type(&Type::Object(fields), &mut object);
+
+            obj.insert("anyOf".to_string(),
+                       Value::Array(vec![Value::Object(name), Value::Object(object)]));
+        }
+        Type::EnumOrArray(variants, elem) => {
+            let mut name = Map::new();
+            gen_type(&Type::Enum(variants), &mut name);
+
+            let mut array = Map::new();
+            gen_type(&Type::Array(elem), &mut array);
+
+            obj.insert("anyOf".to_string(),
+                       Value::Array(vec![Value::Object(name), Value::Object(array)]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_1() {
+        let s = json_schema();
+        assert_eq!(s["type"], "object");
+        assert_eq!(s["properties"]["data"]["required"][0], "csv");
+        // either 'value' or 'start' and 'end' must be set
+        assert_eq!(s["properties"]["items"]["items"]["required"][0], "name");
+        assert_eq!(s["properties"]["items"]["items"]["required"].as_array().unwrap().len(), 1);
+        assert_eq!(s["properties"]["hor_axis"]["properties"]["ticks"]["items"]["type"], "number");
+        assert_eq!(s["properties"]["hor_axis"]["properties"]["width"]["type"], "integer");
+        assert_eq!(s["properties"]["hor_axis"]["properties"]["scale"]["enum"][1], "log");
+        assert_eq!(s["properties"]["theme"]["anyOf"][0]["enum"][2], "dark");
+        assert_eq!(s["properties"]["palette"]["anyOf"][1]["items"]["type"], "string");
+    }
+}
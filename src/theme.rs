@@ -0,0 +1,237 @@
+use serde_json::Value;
+
+/// Names of the built-in themes.
+pub const NAMES: &[&str] = &["default", "minimal", "dark", "print"];
+
+/// Colors, strokes and font styles of all chart elements.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Theme<'a> {
+    /// `none` for a transparent background.
+    pub background: &'a str,
+    pub text_color: &'a str,
+    pub title_font_weight: &'a str,
+    pub group_header_font_weight: &'a str,
+    pub axis_title_font_style: &'a str,
+    pub bar_color: &'a str,
+    pub other_bar_color: &'a str,
+    /// The first tick.
+    pub axis_color: &'a str,
+    pub tick_color: &'a str,
+    pub minor_tick_color: &'a str,
+    pub tick_text_color: &'a str,
+    /// A color of annotations inside bars.
+    pub annotation_text_color: &'a str,
+    /// A color of annotations outside bars.
+    pub annotation_text_color_alt: &'a str,
+    pub annotation_handle_color: &'a str,
+    pub break_marker_color: &'a str,
+    pub break_marker_width: u32,
+    pub whisker_color: &'a str,
+    pub whisker_width: u32,
+    pub target_color: &'a str,
+    pub reference_line_color: &'a str,
+    pub reference_line_width: u32,
+    pub band_color: &'a str,
+    pub band_text_color: &'a str,
+    pub group_separator_color: &'a str,
+}
+
+static DEFAULT: Theme<'static> = Theme {
+    background: "none",
+    text_color: "#000",
+    title_font_weight: "bold",
+    group_header_font_weight: "bold",
+    axis_title_font_style: "italic",
+    bar_color: "#3260cd",
+    other_bar_color: "#999",
+    axis_color: "#333",
+    tick_color: "#ccc",
+    minor_tick_color: "#eee",
+    tick_text_color: "#000",
+    annotation_text_color: "#fff",
+    annotation_text_color_alt: "#000",
+    annotation_handle_color: "#999",
+    break_marker_color: "#fff",
+    break_marker_width: 3,
+    whisker_color: "#333",
+    whisker_width: 1,
+    target_color: "#000",
+    reference_line_color: "#d62728",
+    reference_line_width: 1,
+    band_color: "#fdf1d8",
+    band_text_color: "#000",
+    group_separator_color: "#999",
+};
+
+static MINIMAL: Theme<'static> = Theme {
+    title_font_weight: "normal",
+    axis_title_font_style: "normal",
+    bar_color: "#555",
+    other_bar_color: "#bbb",
+    axis_color: "#999",
+    tick_color: "#eee",
+    minor_tick_color: "#f6f6f6",
+    tick_text_color: "#666",
+    annotation_handle_color: "#ccc",
+    whisker_color: "#000",
+    band_color: "#f2f2f2",
+    band_text_color: "#666",
+    group_separator_color: "#ddd",
+    ..DEFAULT
+};
+
+static DARK: Theme<'static> = Theme {
+    background: "#1e1e1e",
+    text_color: "#ddd",
+    bar_color: "#4e8fe8",
+    other_bar_color: "#777",
+    axis_color: "#aaa",
+    tick_color: "#444",
+    minor_tick_color: "#2c2c2c",
+    tick_text_color: "#bbb",
+    annotation_text_color: "#fff",
+    annotation_text_color_alt: "#ddd",
+    annotation_handle_color: "#777",
+    break_marker_color: "#1e1e1e",
+    whisker_color: "#eee",
+    target_color: "#fff",
+    reference_line_color: "#ff6b6b",
+    band_color: "#3a3320",
+    band_text_color: "#bbb",
+    group_separator_color: "#666",
+    ..DEFAULT
+};
+
+static PRINT: Theme<'static> = Theme {
+    background: "#fff",
+    bar_color: "#666",
+    other_bar_color: "#aaa",
+    axis_color: "#000",
+    tick_color: "#bbb",
+    minor_tick_color: "#ddd",
+    annotation_handle_color: "#666",
+    whisker_color: "#000",
+    reference_line_color: "#000",
+    reference_line_width: 2,
+    band_color: "#eee",
+    group_separator_color: "#000",
+    ..DEFAULT
+};
+
+impl<'a> Default for Theme<'a> {
+    fn default() -> Theme<'a> {
+        DEFAULT
+    }
+}
+
+impl<'a> Theme<'a> {
+    /// Returns a built-in theme.
+    pub fn by_name(name: &str) -> Option<Theme<'static>> {
+        match name {
+            "default" => Some(DEFAULT),
+            "minimal" => Some(MINIMAL),
+            "dark" => Some(DARK),
+            "print" => Some(PRINT),
+            _ => None,
+        }
+    }
+
+    /// Returns all color fields, except the background, with their config paths.
+    pub fn colors(&self) -> [(&'static str, &'a str); 17] {
+        [
+            ("/theme/text_color", self.text_color),
+            ("/theme/bar_color", self.bar_color),
+            ("/theme/other_bar_color", self.other_bar_color),
+            ("/theme/axis_color", self.axis_color),
+            ("/theme/tick_color", self.tick_color),
+            ("/theme/minor_tick_color", self.minor_tick_color),
+            ("/theme/tick_text_color", self.tick_text_color),
+            ("/theme/annotation_text_color", self.annotation_text_color),
+            ("/theme/annotation_text_color_alt", self.annotation_text_color_alt),
+            ("/theme/annotation_handle_color", self.annotation_handle_color),
+            ("/theme/break_marker_color", self.break_marker_color),
+            ("/theme/whisker_color", self.whisker_color),
+            ("/theme/target_color", self.target_color),
+            ("/theme/reference_line_color", self.reference_line_color),
+            ("/theme/band_color", self.band_color),
+            ("/theme/band_text_color", self.band_text_color),
+            ("/theme/group_separator_color", self.group_separator_color),
+        ]
+    }
+
+    /// Overrides a field by name.
+    ///
+    /// Unknown fields and values with a wrong type are ignored.
+    pub fn set_field(&mut self, name: &str, value: &'a Value) {
+        if let Some(n) = value.as_u64() {
+            match name {
+                "break_marker_width" => self.break_marker_width = n as u32,
+                "whisker_width" => self.whisker_width = n as u32,
+                "reference_line_width" => self.reference_line_width = n as u32,
+                _ => {}
+            }
+
+            return;
+        }
+
+        let s = match value.as_str() {
+            Some(s) => s,
+            None => return,
+        };
+
+        match name {
+            "background" => self.background = s,
+            "text_color" => self.text_color = s,
+            "title_font_weight" => self.title_font_weight = s,
+            "group_header_font_weight" => self.group_header_font_weight = s,
+            "axis_title_font_style" => self.axis_title_font_style = s,
+            "bar_color" => self.bar_color = s,
+            "other_bar_color" => self.other_bar_color = s,
+            "axis_color" => self.axis_color = s,
+            "tick_color" => self.tick_color = s,
+            "minor_tick_color" => self.minor_tick_color = s,
+            "tick_text_color" => self.tick_text_color = s,
+            "annotation_text_color" => self.annotation_text_color = s,
+            "annotation_text_color_alt" => self.annotation_text_color_alt = s,
+            "annotation_handle_color" => self.annotation_handle_color = s,
+            "break_marker_color" => self.break_marker_color = s,
+            "whisker_color" => self.whisker_color = s,
+            "target_color" => self.target_color = s,
+            "reference_line_color" => self.reference_line_color = s,
+            "band_color" => self.band_color = s,
+            "band_text_color" => self.band_text_color = s,
+            "group_separator_color" => self.group_separator_color = s,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_name_1() {
+        for name in NAMES {
+            assert!(Theme::by_name(name).is_some());
+        }
+
+        assert_eq!(Theme::by_name("default"), Some(Theme::default()));
+        assert_eq!(Theme::by_name("light"), None);
+    }
+
+    #[test]
+    fn set_field_1() {
+        let bar_color = Value::from("#f00");
+        let width = Value::from(2);
+
+        let mut theme = Theme::default();
+        theme.set_field("bar_color", &bar_color);
+        theme.set_field("whisker_width", &width);
+        theme.set_field("whisker_color", &width);
+
+        assert_eq!(theme.bar_color, "#f00");
+        assert_eq!(theme.whisker_width, 2);
+        assert_eq!(theme.whisker_color, Theme::default().whisker_color);
+    }
+}
@@ -0,0 +1,277 @@
+//! Durations and dates.
+//!
+//! Both are stored in seconds. Dates are seconds since the Unix epoch in UTC.
+
+use number_format::{NumberFormat, Precision};
+
+/// Units accepted in duration strings.
+static INPUT_UNITS: &[(&str, f64)] = &[
+    ("ns", 1e-9),
+    ("us", 1e-6),
+    ("µs", 1e-6),
+    ("ms", 1e-3),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("min", 60.0),
+    ("h", 3600.0),
+    ("d", 86400.0),
+];
+
+/// Units used for formatting. From the largest to the smallest.
+static OUTPUT_UNITS: &[(&str, f64)] = &[
+    ("d", 86400.0),
+    ("h", 3600.0),
+    ("min", 60.0),
+    ("s", 1.0),
+    ("ms", 1e-3),
+    ("us", 1e-6),
+    ("ns", 1e-9),
+];
+
+static SECONDS_PER_DAY: i64 = 86400;
+
+/// Returns a number of seconds in the unit.
+pub fn unit_factor(name: &str) -> Option<f64> {
+    INPUT_UNITS.iter().find(|u| u.0 == name).map(|u| u.1)
+}
+
+/// Parses a duration like `1.5s`, `230ms` or `1h 30m` into seconds.
+pub fn parse_duration(text: &str) -> Option<f64> {
+    let mut s = text.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.0;
+    while !s.is_empty() {
+        let n = s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+        let value: f64 = s[..n].parse().ok()?;
+        s = s[n..].trim_start();
+
+        let n = s.find(|c: char| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+                 .unwrap_or(s.len());
+        total += value * unit_factor(&s[..n])?;
+        s = s[n..].trim_start();
+    }
+
+    Some(total)
+}
+
+/// Parses a date like `2018-03-25` or `2018-03-25T14:30:00Z` into a Unix timestamp.
+///
+/// Time zones are not supported. All dates are in UTC. Years are limited to 0-9999.
+pub fn parse_date(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let text = text.strip_suffix('Z').unwrap_or(text);
+
+    let (date, time) = match text.find(['T', ' ']) {
+        Some(idx) => (&text[..idx], Some(&text[idx + 1..])),
+        None => (text, None),
+    };
+
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let year: i64 = parts[0].parse().ok()?;
+    let month: u32 = parts[1].parse().ok()?;
+    let day: u32 = parts[2].parse().ok()?;
+
+    // larger years would overflow the day count
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    // an invalid date, like 02-30, will be shifted
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    let mut secs = (days * SECONDS_PER_DAY) as f64;
+
+    if let Some(time) = time {
+        let parts: Vec<&str> = time.split(':').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return None;
+        }
+
+        let hours: u32 = parts[0].parse().ok()?;
+        let minutes: u32 = parts[1].parse().ok()?;
+        let seconds: f64 = match parts.get(2) {
+            Some(s) => s.parse().ok()?,
+            None => 0.0,
+        };
+
+        if hours > 23 || minutes > 59 || !(0.0..60.0).contains(&seconds) {
+            return None;
+        }
+
+        secs += (hours * 3600 + minutes * 60) as f64 + seconds;
+    }
+
+    Some(secs)
+}
+
+/// Formats a duration using the largest unit that fits the value.
+///
+/// Uses three significant digits unless the precision is set explicitly.
+pub fn format_duration(value: f64, nf: &NumberFormat) -> String {
+    let (name, factor) = select_unit(value);
+
+    let mut nf = nf.clone();
+    if nf.precision == Precision::Auto {
+        nf.precision = Precision::Significant(3);
+    }
+
+    let suffix = nf.suffix;
+    nf.suffix = "";
+    nf.format(value / factor) + name + suffix
+}
+
+/// Formats a duration tick using the unit of the step between ticks.
+pub fn format_duration_tick(value: f64, step: f64, nf: &NumberFormat) -> String {
+    let (name, factor) = select_unit(step);
+
+    let mut nf = nf.clone();
+    let suffix = nf.suffix;
+    nf.suffix = "";
+    // remove an error of the float division
+    let n = (value / factor * 1e9).round() / 1e9;
+    nf.format(n) + name + suffix
+}
+
+/// Formats a date tick. Ticks at midnight are shown as dates, others as time.
+pub fn format_date_tick(value: f64, step: f64) -> String {
+    let (days, secs) = split_timestamp(value);
+
+    if step >= SECONDS_PER_DAY as f64 || secs == 0 {
+        format_day(days)
+    } else if step >= 60.0 {
+        format!("{:02}:{:02}", secs / 3600, secs % 3600 / 60)
+    } else {
+        format_time(secs)
+    }
+}
+
+/// Formats a date. The time is omitted at midnight.
+pub fn format_timestamp(value: f64) -> String {
+    let (days, secs) = split_timestamp(value);
+
+    if secs == 0 {
+        format_day(days)
+    } else if secs % 60 == 0 {
+        format!("{} {:02}:{:02}", format_day(days), secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{} {}", format_day(days), format_time(secs))
+    }
+}
+
+fn select_unit(value: f64) -> (&'static str, f64) {
+    for &(name, factor) in OUTPUT_UNITS {
+        // allow an error of the float multiplication
+        if value.abs() >= factor * (1.0 - 1e-9) {
+            return (name, factor);
+        }
+    }
+
+    // zero
+    ("s", 1.0)
+}
+
+/// Returns days since the epoch and seconds since midnight.
+fn split_timestamp(value: f64) -> (i64, i64) {
+    let secs = value.round() as i64;
+    (secs.div_euclid(SECONDS_PER_DAY), secs.rem_euclid(SECONDS_PER_DAY))
+}
+
+fn format_day(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn format_time(secs: i64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_1() {
+        assert_eq!(parse_duration("1.5s"), Some(1.5));
+        assert_eq!(parse_duration("230ms"), Some(0.23));
+        assert_eq!(parse_duration("1h 30m"), Some(5400.0));
+        assert_eq!(parse_duration("2 min"), Some(120.0));
+        assert_eq!(parse_duration("15"), None);
+        assert_eq!(parse_duration("5 sec"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn parse_date_1() {
+        assert_eq!(parse_date("1970-01-01"), Some(0.0));
+        assert_eq!(parse_date("2018-03-25"), Some(1521936000.0));
+        assert_eq!(parse_date("2018-03-25T14:30:00Z"), Some(1521988200.0));
+        assert_eq!(parse_date("2018-03-25 14:30"), Some(1521988200.0));
+        assert_eq!(parse_date("2018-02-30"), None);
+        assert_eq!(parse_date("2018-03-25T25:00"), None);
+        assert_eq!(parse_date("25.03.2018"), None);
+        assert_eq!(parse_date("9999-12-31"), Some(253402214400.0));
+        assert_eq!(parse_date("99999999999999-01-01"), None);
+    }
+
+    #[test]
+    fn format_duration_1() {
+        let nf = NumberFormat::default();
+        assert_eq!(format_duration(1.5, &nf), "1.5s");
+        assert_eq!(format_duration(0.23, &nf), "230ms");
+        assert_eq!(format_duration(150.0, &nf), "2.5min");
+        assert_eq!(format_duration(0.0, &nf), "0s");
+    }
+
+    #[test]
+    fn format_duration_tick_1() {
+        let nf = NumberFormat::default();
+        assert_eq!(format_duration_tick(0.0, 0.2, &nf), "0ms");
+        assert_eq!(format_duration_tick(0.6, 0.2, &nf), "600ms");
+        assert_eq!(format_duration_tick(90.0, 30.0, &nf), "90s");
+        assert_eq!(format_duration_tick(2700.0, 900.0, &nf), "45min");
+    }
+
+    #[test]
+    fn format_date_1() {
+        assert_eq!(format_date_tick(1521936000.0, 86400.0), "2018-03-25");
+        assert_eq!(format_date_tick(1521988200.0, 3600.0), "14:30");
+        assert_eq!(format_date_tick(1521936000.0, 3600.0), "2018-03-25");
+        assert_eq!(format_timestamp(1521988200.0), "2018-03-25 14:30");
+        assert_eq!(format_timestamp(1521988215.0), "2018-03-25 14:30:15");
+        assert_eq!(format_timestamp(-86400.0), "1969-12-31");
+    }
+}